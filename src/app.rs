@@ -1,7 +1,18 @@
 use crate::{app::iced::event::listen_raw, subscriptions::launcher};
-use crate::wayland_subscription::{WaylandUpdate, ToplevelUpdate, WaylandImage, wayland_subscription};
-use cosmic::cctk::toplevel_info::ToplevelInfo;
-use cosmic::cctk::wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1;
+use crate::capabilities::Capabilities;
+use crate::config::{LaunchCommand, LaunchPolicy, LauncherConfig};
+use crate::desktop_index::{self, AppEntry, DesktopIndex};
+use crate::gpus::{self, GpuInfo};
+use crate::notifications::{self, Notification};
+use crate::providers::{self, ProvidedItem, Provider, ProviderAction, ProviderContext, ToplevelProvider};
+use crate::run_command;
+use crate::switcher;
+use crate::thumbnail_cache::{self, ThumbnailCache};
+use crate::wayland_subscription::{
+    Toplevel, ToplevelHandle, ToplevelRequest, ToplevelState, ToplevelUpdate, WaylandImage,
+    WaylandRequest, WaylandUpdate, WorkspaceInfo, WorkspaceRequest, wayland_subscription,
+};
+use cosmic::cctk::wayland_protocols::ext::workspace::v1::client::ext_workspace_handle_v1::ExtWorkspaceHandleV1;
 use clap::Parser;
 use cosmic::app::{Core, CosmicFlags, Settings, Task};
 use cosmic::dbus_activation::Details;
@@ -19,8 +30,8 @@ use cosmic::iced::platform_specific::shell::commands::{
     activation::request_token,
     layer_surface::{Anchor, KeyboardInteractivity, destroy_layer_surface, get_layer_surface},
 };
-use cosmic::iced::widget::{column, container, image::{Handle, Image}};
-use cosmic::iced::{self, Length, Size, Subscription};
+use cosmic::iced::widget::{column, container, image::{Handle, Image}, stack};
+use cosmic::iced::{self, Length, Padding, Size, Subscription};
 use cosmic::iced_core::keyboard::key::Named;
 use cosmic::iced_core::widget::operation;
 use cosmic::iced_core::{Point, Rectangle, window};
@@ -36,21 +47,27 @@ use cosmic::widget::{
     mouse_area, text,
     text_input,
 };
-use cosmic::iced::widget::text::Wrapping;
+use cosmic::iced::widget::text::{Span, Wrapping};
 use cosmic::{Element, keyboard_nav};
+use cosmic::desktop::DesktopEntryData;
 use cosmic::iced_runtime;
 use iced::keyboard::Key;
 use pop_launcher::{ContextOption, GpuPreference, IconSource, SearchResult};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     str::FromStr,
     time::{Duration, Instant},
 };
 use tokio::sync::mpsc;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+
+/// Thumbnails older than this are captured again when the switcher opens.
+const THUMBNAIL_MAX_AGE: Duration = Duration::from_secs(2);
+/// How long a launched app may take to open its first window.
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(15);
 
 static INPUT_ID: LazyLock<Id> = LazyLock::new(|| Id::new("input_id"));
 static SCROLLABLE: LazyLock<Id> = LazyLock::new(|| Id::new("scrollable"));
@@ -71,6 +88,14 @@ pub enum LauncherTasks {
     AltTab,
     #[clap(about = "Toggle the launcher and switch to the alt-tab view")]
     ShiftAltTab,
+    #[clap(about = "Toggle an overview of the windows on the current workspace")]
+    Overview,
+    #[clap(about = "Show the workspaces, or select the next one when already shown")]
+    Workspaces,
+    #[clap(about = "Focus the window or workspace that most recently asked for attention")]
+    Urgent,
+    #[clap(about = "Print which wayland protocols and features are available")]
+    Diagnose,
 }
 
 impl Display for LauncherTasks {
@@ -98,6 +123,10 @@ impl CosmicFlags for Args {
 
 pub fn run() -> cosmic::iced::Result {
     let args = Args::parse();
+    if let Some(LauncherTasks::Diagnose) = args.subcommand {
+        diagnose();
+        return Ok(());
+    }
     cosmic::app::run_single_instance::<CosmicLauncher>(
         Settings::default()
             .antialiasing(true)
@@ -111,6 +140,58 @@ pub fn run() -> cosmic::iced::Result {
     )
 }
 
+/// Sizes of the Alt+Tab grid, derived from the surface width.
+#[derive(Clone, Copy, Debug)]
+struct CardLayout {
+    grid_width: f32,
+    card_width: f32,
+    preview: Size,
+}
+
+/// Arrow key direction, used for spatial navigation in the overview.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Where Shift+arrows or Shift+1..9 send the window selected in the switcher.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveTarget {
+    /// Workspace at this index in the window's workspace group.
+    Workspace(usize),
+    PreviousWorkspace,
+    NextWorkspace,
+    /// Active workspace of the previous or next output.
+    PreviousOutput,
+    NextOutput,
+}
+
+/// An application started from the launcher, tracked until it opens a window.
+#[derive(Clone, Debug)]
+struct PendingLaunch {
+    id: u64,
+    token: Option<String>,
+    app_id: String,
+    /// Whether a window is expected, otherwise the launch is only tracked
+    /// until it was spawned.
+    expect_window: bool,
+}
+
+/// Prints a report of the protocols the compositor supports and the
+/// launcher features that depend on them.
+fn diagnose() {
+    match Capabilities::probe() {
+        Ok(capabilities) => print!("{}", capabilities.report()),
+        Err(err) => {
+            eprintln!("Failed to probe the compositor: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SurfaceState {
     Visible,
@@ -125,6 +206,9 @@ pub struct CosmicLauncher {
     input_value: String,
     surface_state: SurfaceState,
     launcher_items: Vec<SearchResult>,
+    launcher_results: Vec<SearchResult>, // Latest pop-launcher results, merged into `launcher_items`
+    providers: Vec<Box<dyn Provider>>,
+    provider_items: HashMap<u32, ProvidedItem>, // Provider results in `launcher_items` by id
     tx: Option<mpsc::Sender<launcher::Request>>,
     menu: Option<(u32, Vec<ContextOption>)>,
     cursor_position: Option<Point<f32>>,
@@ -132,19 +216,47 @@ pub struct CosmicLauncher {
     last_hide: Instant,
     alt_tab_mode: bool, // Track if we're in Alt+Tab mode
     super_launcher_mode: bool, // Track if we're in Super key launcher mode (Alt+Tab list with search)
+    overview_mode: bool, // Spatial overview, a variant of Alt+Tab mode
+    workspace_mode: bool, // Workspace overview, `active` indexes `workspaces`
     window_id: window::Id,
     queue: VecDeque<Message>,
     result_ids: Vec<Id>,
     overlap: HashMap<String, Rectangle>,
     margin: f32,
     height: f32,
+    width: f32,
     needs_clear: bool,
     search_debounce_timer: Option<Instant>, // Timer for debounced search
 
-    toplevel_captures: HashMap<ExtForeignToplevelHandleV1, WaylandImage>,
-    screenshot_cache_time: HashMap<ExtForeignToplevelHandleV1, Instant>,
-    toplevels: Vec<ToplevelInfo>,
+    thumbnails: ThumbnailCache<ToplevelHandle>,
+    toplevels: Vec<Toplevel>,
+    workspaces: Vec<WorkspaceInfo>,
+    workspace_thumbnails: ThumbnailCache<ExtWorkspaceHandleV1>,
     active: Option<usize>, // For Alt+Tab selected window index
+    alt_tab_origin: Option<ToplevelHandle>, // Window focused when the switcher opened
+    switcher_filter: String, // Typed in the switcher, keeps it open once Alt is released
+    urgent_since: HashMap<ToplevelHandle, Instant>, // When each window started asking for attention
+    desktop_index: Arc<DesktopIndex>, // Names and icons of apps by app_id
+    desktop_index_loading: bool,
+    config: LauncherConfig,
+    force_new_instance: bool, // Shift+Enter skips the launch policy for the next desktop entry
+    pending_launches: Vec<PendingLaunch>, // Launched apps that have not opened a window yet
+    gpus: Vec<GpuInfo>, // Cached switcheroo-control GPUs, indexed like `GpuPreference::SpecificIdx`
+    gpus_loading: bool,
+    launch_gpu: Option<usize>, // GPU picked with Ctrl+G for Ctrl+Enter
+    gpu_override: Option<GpuPreference>, // Set by Ctrl+Enter for the next desktop entry
+    expanded: Option<(u32, Vec<ContextOption>)>, // Search result showing its desktop actions
+    expanded_focus: Option<usize>, // Action selected with the arrow keys
+    expand_requested: Option<u32>, // Result whose actions were asked from pop-launcher
+    without_actions: HashSet<u32>, // Results pop-launcher listed no actions for, until the next search
+    run_history: run_command::History,
+    executables: Option<Arc<Vec<String>>>, // Loaded the first time run mode is entered
+    run_suggestions: Vec<String>,
+    run_focus: Option<usize>, // Selected suggestion, `None` runs the input as typed
+    next_launch_id: u64,
+    wayland_tx: Option<calloop::channel::Sender<WaylandRequest>>,
+    thumbnail_size: Option<(u32, u32)>, // Preview size last sent to the wayland thread
+    capabilities: Capabilities,
     #[allow(dead_code)]
     backend_event_receiver: Option<mpsc::UnboundedReceiver<WaylandUpdate>>,
 }
@@ -157,6 +269,28 @@ pub enum Message {
     Activate(Option<usize>),
     CursorMoved(Point<f32>),
     Hide,
+    Cancel,
+    CloseWindow, // Close the window selected in the switcher
+    // Shift+Enter: send the window focused before opening to the selected
+    // workspace, or start a new instance of the selected application
+    ShiftActivate,
+    Config(LauncherConfig),
+    MoveSelected(MoveTarget), // Send the switcher selection to another workspace or output
+    Select(usize), // Select a switcher item without activating it
+    SwitcherType(String), // Character typed while the switcher is open
+    SwitcherCharacter(String, String, bool, bool), // Unmodified character key and the typed text, with whether Alt and Shift were held
+    SwitcherBackspace,
+    JumpToUrgent, // Focus the window that most recently asked for attention
+    DesktopIndex(Option<Arc<DesktopIndex>>), // Desktop entries finished loading, `None` when unchanged
+    Launched(u64, Result<(), String>),
+    Gpus(Vec<GpuInfo>),
+    GpuActivate, // Ctrl+Enter: launch the selected app on another GPU
+    CycleGpu, // Ctrl+G: pick the GPU Ctrl+Enter launches on
+    ExpandActions(usize), // Show or hide the desktop actions of a search result
+    ActivateAction(u32, u32), // Result id and context option id of a desktop action
+    Executables(Arc<Vec<String>>), // Programs in PATH, for run mode completion
+    LaunchTimeout(u64),
+    Navigate(Direction),
     LauncherEvent(launcher::Event),
     Layer(LayerEvent),
     KeyboardNav(keyboard_nav::Action),
@@ -177,39 +311,649 @@ impl CosmicLauncher {
         if alt_tab && super_launcher {
             panic!("Cannot have both alt_tab_mode and super_launcher_mode active simultaneously");
         }
-        println!("DEBUG: Mode set - alt_tab: {}, super_launcher: {} (previous: alt_tab={}, super={})", 
-                 alt_tab, super_launcher, self.alt_tab_mode, self.super_launcher_mode);
+        debug!("Mode set - alt_tab: {}, super_launcher: {} (previous: alt_tab={}, super={})", 
+            alt_tab, super_launcher, self.alt_tab_mode, self.super_launcher_mode);
         self.alt_tab_mode = alt_tab;
         self.super_launcher_mode = super_launcher;
+        self.overview_mode = false;
+        self.workspace_mode = false;
     }
 
-    fn is_screenshot_cache_fresh(&self, handle: &ExtForeignToplevelHandleV1) -> bool {
-        const CACHE_DURATION_MS: u128 = 2000; // Cache screenshots for 2 seconds
-        
-        if let Some(cache_time) = self.screenshot_cache_time.get(handle) {
-            let age = cache_time.elapsed().as_millis();
-            age < CACHE_DURATION_MS
-        } else {
-            false
+    /// Requests new captures for toplevels whose thumbnail is missing or old.
+    fn refresh_stale_thumbnails(&self) {
+        if !self.capabilities.thumbnails() {
+            return;
         }
+        for toplevel in &self.toplevels {
+            if self
+                .thumbnails
+                .is_stale(&toplevel.handle, THUMBNAIL_MAX_AGE)
+            {
+                self.wayland_request(WaylandRequest::Screencopy(toplevel.handle.clone()));
+            }
+        }
+    }
+
+    /// Refilters the switcher after the filter changed, selecting the best
+    /// match.
+    fn filter_switcher(&mut self) {
+        self.populate_from_cached_toplevels();
+        self.active = (!self.launcher_items.is_empty()).then_some(0);
+    }
+
+    /// Whether a filter was typed in the switcher, whitespace alone does not count.
+    fn switcher_filtering(&self) -> bool {
+        !self.switcher_filter.trim().is_empty()
     }
 
     fn populate_from_cached_toplevels(&mut self) {
         // Immediately populate launcher_items from cached toplevels for Alt+Tab
-        println!("DEBUG: Populating {} toplevels from cache", self.toplevels.len());
+        debug!("Populating {} toplevels from cache", self.toplevels.len());
         
-        self.launcher_items = self.toplevels.iter().enumerate().map(|(idx, toplevel)| {
-            SearchResult {
-                id: idx as u32, // Use index as simple ID
-                name: if !toplevel.title.is_empty() { toplevel.title.clone() } else if !toplevel.app_id.is_empty() { toplevel.app_id.clone() } else { "Unknown".to_string() },
-                description: toplevel.app_id.clone(),
-                icon: None, // Will be determined in UI based on app_id
-                category_icon: None,
-                window: None, // We'll match screenshots by name/title instead
-            }
+        let items = self.toplevels.iter().enumerate().filter_map(|(idx, toplevel)| {
+            let app = self.desktop_index.lookup(&toplevel.app_id);
+            let score = switcher::filter_score(&self.switcher_filter, &toplevel.title, app.map(|app| app.name.as_str()))?;
+            let minimized = toplevel.state.contains(&ToplevelState::Minimized);
+            Some((score, minimized, toplevel_item(idx, toplevel, app)))
         }).collect();
+        self.launcher_items = switcher::filter_order(items);
         
-        println!("DEBUG: Populated {} launcher items from toplevels", self.launcher_items.len());
+        debug!("Populated {} launcher items from toplevels", self.launcher_items.len());
+    }
+
+    /// Fills the overview with the windows sharing the focused window's output
+    /// and workspace. Windows without known geometry are left out.
+    fn populate_overview(&mut self) {
+        let focused = self
+            .toplevels
+            .iter()
+            .find(|t| t.state.contains(&ToplevelState::Activated))
+            .or_else(|| self.toplevels.iter().find(|t| t.geometry().is_some()));
+        let Some((output, _)) = focused.and_then(|t| t.geometry()) else {
+            self.launcher_items.clear();
+            return;
+        };
+        let workspaces = &focused.unwrap().workspaces;
+
+        self.launcher_items = self
+            .toplevels
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.geometry.contains_key(output))
+            .filter(|(_, t)| !t.state.contains(&ToplevelState::Minimized))
+            .filter(|(_, t)| workspaces.is_empty() || !t.workspaces.is_disjoint(workspaces))
+            .map(|(idx, toplevel)| {
+                toplevel_item(idx, toplevel, self.desktop_index.lookup(&toplevel.app_id))
+            })
+            .collect();
+        debug!("Overview shows {} windows", self.launcher_items.len());
+    }
+
+    /// Opens the workspace overview, or selects the next workspace when it is
+    /// already shown so repeated Super+Tab presses cycle.
+    fn open_workspaces(&mut self) -> Task<Message> {
+        if self.workspace_mode && self.surface_state != SurfaceState::Hidden {
+            self.select_workspace(1);
+            return Task::none();
+        }
+        self.set_mode(false, false);
+        self.workspace_mode = true;
+        self.launcher_items.clear();
+        self.active = self
+            .workspaces
+            .iter()
+            .position(|w| w.active)
+            .or((!self.workspaces.is_empty()).then_some(0));
+        for workspace in self.workspaces.iter().filter(|w| w.active) {
+            self.wayland_request(WaylandRequest::Workspace(WorkspaceRequest::Capture(
+                workspace.handle.clone(),
+            )));
+        }
+        self.show()
+    }
+
+    fn select_workspace(&mut self, delta: isize) {
+        let len = self.workspaces.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let current = self.active.unwrap_or(0) as isize;
+        self.active = Some((current + delta).rem_euclid(len) as usize);
+    }
+
+    fn activate_selected_workspace(&mut self) -> Task<Message> {
+        if let Some(workspace) = self.active.and_then(|idx| self.workspaces.get(idx)) {
+            self.wayland_request(WaylandRequest::Workspace(WorkspaceRequest::Activate(
+                workspace.handle.clone(),
+            )));
+        }
+        self.hide()
+    }
+
+    /// Index in `workspaces` of the workspace a toplevel is on.
+    fn toplevel_workspace(&self, toplevel: &Toplevel) -> Option<usize> {
+        self.workspaces
+            .iter()
+            .position(|w| toplevel.workspaces.contains(&w.handle))
+    }
+
+    /// Sends the window selected in the switcher to `target`. The card badge
+    /// follows once the compositor reports the new workspace.
+    fn move_selected(&self, target: MoveTarget) {
+        let Some(toplevel) = self
+            .active
+            .and_then(|idx| self.launcher_items.get(idx))
+            .and_then(|item| self.toplevels.get(item.id as usize))
+        else {
+            return;
+        };
+        let Some(current) = self.toplevel_workspace(toplevel).map(|i| &self.workspaces[i]) else {
+            debug!("No known workspace for {:?}", toplevel.handle);
+            return;
+        };
+        let group: Vec<_> = self
+            .workspaces
+            .iter()
+            .filter(|w| w.group == current.group)
+            .collect();
+        let position = group.iter().position(|w| w.handle == current.handle).unwrap_or(0);
+
+        let target = match target {
+            MoveTarget::Workspace(idx) => group.get(idx).copied(),
+            MoveTarget::PreviousWorkspace => position.checked_sub(1).and_then(|i| group.get(i).copied()),
+            MoveTarget::NextWorkspace => group.get(position + 1).copied(),
+            MoveTarget::PreviousOutput | MoveTarget::NextOutput => {
+                // Groups in the order the compositor listed them
+                let mut groups = Vec::new();
+                for workspace in &self.workspaces {
+                    if !groups.contains(&&workspace.group) {
+                        groups.push(&workspace.group);
+                    }
+                }
+                let current_group = groups.iter().position(|g| **g == current.group).unwrap_or(0);
+                let next_group = if target == MoveTarget::NextOutput {
+                    groups.get(current_group + 1)
+                } else {
+                    current_group.checked_sub(1).and_then(|i| groups.get(i))
+                };
+                next_group.and_then(|group| {
+                    self.workspaces
+                        .iter()
+                        .find(|w| &w.group == *group && w.active)
+                })
+            }
+        };
+        let Some(target) = target.filter(|w| w.handle != current.handle) else {
+            return;
+        };
+        self.wayland_request(WaylandRequest::Toplevel(ToplevelRequest::MoveToWorkspace(
+            toplevel.handle.clone(),
+            target.handle.clone(),
+        )));
+    }
+
+    /// Name of the workspace a switcher item's window is on.
+    fn workspace_badge(&self, item: &SearchResult) -> Option<String> {
+        if !self.alt_tab_mode {
+            return None;
+        }
+        let toplevel = self.toplevels.get(item.id as usize)?;
+        let workspace = &self.workspaces[self.toplevel_workspace(toplevel)?];
+        if workspace.urgent {
+            // The compositor only tells which workspace needs attention
+            Some(format!("Workspace {} · needs attention", workspace.name))
+        } else {
+            Some(format!("Workspace {}", workspace.name))
+        }
+    }
+
+    /// Labels for the state of a switcher item's window.
+    fn state_badges(&self, item: &SearchResult) -> Vec<&'static str> {
+        let Some(toplevel) = self
+            .toplevels
+            .get(item.id as usize)
+            .filter(|_| self.alt_tab_mode)
+        else {
+            return Vec::new();
+        };
+        [
+            (ToplevelState::Minimized, "Minimized"),
+            (ToplevelState::Maximized, "Maximized"),
+            (ToplevelState::Fullscreen, "Fullscreen"),
+        ]
+        .into_iter()
+        .filter(|(state, _)| toplevel.state.contains(state))
+        .map(|(_, label)| label)
+        .collect()
+    }
+
+    fn is_urgent(&self, item: &SearchResult) -> bool {
+        self.alt_tab_mode
+            && self
+                .toplevels
+                .get(item.id as usize)
+                .is_some_and(|t| t.state.contains(&ToplevelState::Urgent))
+    }
+
+    /// Activates the window that asked for attention last and closes the
+    /// launcher. When the window is not known, only its workspace, that
+    /// workspace is shown instead.
+    fn jump_to_urgent(&mut self) -> Task<Message> {
+        if let Some(handle) = self
+            .urgent_since
+            .iter()
+            .max_by_key(|(_, since)| **since)
+            .map(|(handle, _)| handle.clone())
+        {
+            info!("Jumping to urgent window {:?}", handle);
+            self.wayland_request(WaylandRequest::Toplevel(ToplevelRequest::Activate(handle)));
+        } else if let Some(workspace) = self.workspaces.iter().find(|w| w.urgent) {
+            info!("Jumping to urgent workspace {}", workspace.name);
+            let handle = workspace.handle.clone();
+            self.wayland_request(WaylandRequest::Workspace(WorkspaceRequest::Activate(handle)));
+        } else {
+            debug!("Nothing is asking for attention");
+            return Task::none();
+        }
+        self.hide()
+    }
+
+    /// Icon of the desktop entry for `app_id`, or the icon named after it.
+    fn app_icon(&self, app_id: &str) -> icon::Handle {
+        match self.desktop_index.lookup(app_id) {
+            Some(app) => app.icon.clone(),
+            None if !app_id.is_empty() => icon::from_name(app_id).handle(),
+            None => icon::from_name("application-x-executable").handle(),
+        }
+    }
+
+    /// Desktop entry icon of a switcher item's window.
+    fn item_icon(&self, item: &SearchResult) -> Option<icon::Handle> {
+        if !self.alt_tab_mode {
+            return None;
+        }
+        let toplevel = self.toplevels.get(item.id as usize)?;
+        Some(self.desktop_index.lookup(&toplevel.app_id)?.icon.clone())
+    }
+
+    /// Reloads the desktop entry index in the background if an application
+    /// directory changed since it was built. Checking for changes walks the
+    /// directories too, so it happens on the same blocking task.
+    fn refresh_desktop_index(&mut self) -> Task<Message> {
+        if self.desktop_index_loading {
+            return Task::none();
+        }
+        self.desktop_index_loading = true;
+        let index = self.desktop_index.clone();
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || index.reloaded())
+                    .await
+                    .ok()
+                    .flatten()
+            },
+            |index| cosmic::Action::App(Message::DesktopIndex(index.map(Arc::new))),
+        )
+    }
+
+    /// Loads the GPUs from switcheroo-control while none are known, in case
+    /// it was not running yet or the last call failed.
+    fn refresh_gpus(&mut self) -> Task<Message> {
+        if self.gpus_loading || !self.gpus.is_empty() {
+            return Task::none();
+        }
+        self.gpus_loading = true;
+        Task::perform(gpus::load_system(), |gpus| cosmic::action::app(Message::Gpus(gpus)))
+    }
+
+    fn app_name(&self, app_id: &str) -> String {
+        self.desktop_index
+            .lookup(app_id)
+            .map_or_else(|| app_id.to_string(), |app| app.name.clone())
+    }
+
+    /// Whether the new window `toplevel` belongs to `launch`. The activation
+    /// token decides when both have one, the app id otherwise.
+    fn launch_matches(&self, launch: &PendingLaunch, toplevel: &Toplevel) -> bool {
+        if let (Some(launched), Some(token)) = (&launch.token, &toplevel.activation_token) {
+            return launched == token;
+        }
+        self.app_matches(&launch.app_id, toplevel)
+    }
+
+    /// Whether `toplevel` is a window of the app with desktop id `app_id`.
+    fn app_matches(&self, app_id: &str, toplevel: &Toplevel) -> bool {
+        if desktop_index::id_matches(app_id, None, &toplevel.app_id) {
+            return true;
+        }
+        // Covers windows only matching through `StartupWMClass`
+        match (self.desktop_index.lookup(app_id), self.desktop_index.lookup(&toplevel.app_id)) {
+            (Some(a), Some(b)) => std::ptr::eq(a, b),
+            _ => false,
+        }
+    }
+
+    /// Stops tracking the launch `id`, returning it if it was still pending.
+    fn finish_launch(&mut self, id: u64) -> Option<PendingLaunch> {
+        let idx = self.pending_launches.iter().position(|launch| launch.id == id)?;
+        Some(self.pending_launches.remove(idx))
+    }
+
+    /// Stops tracking the oldest launch waiting for a window like `toplevel`,
+    /// returning it.
+    fn finish_launch_of(&mut self, toplevel: &Toplevel) -> Option<PendingLaunch> {
+        let id = self
+            .pending_launches
+            .iter()
+            .find(|launch| launch.expect_window && self.launch_matches(launch, toplevel))?
+            .id;
+        self.finish_launch(id)
+    }
+
+    /// Environment selecting `gpu` for a launched app.
+    fn gpu_envs(&self, gpu: GpuPreference) -> Option<HashMap<String, String>> {
+        match gpu {
+            GpuPreference::Default => self.gpus.iter().find(|gpu| gpu.default),
+            GpuPreference::NonDefault => self.gpus.iter().find(|gpu| !gpu.default),
+            GpuPreference::SpecificIdx(idx) => self.gpus.get(idx as usize),
+        }
+        .map(|gpu| gpu.environment.clone())
+    }
+
+    /// Whether `item` is a pop-launcher desktop entry result, which are the
+    /// only ones launched with a GPU and desktop actions.
+    fn is_desktop_entry(&self, item: &SearchResult) -> bool {
+        item.window.is_none()
+            && !self.provider_items.contains_key(&item.id)
+            && self.desktop_index.has_name(&item.name)
+    }
+
+    /// Whether `item` gets a chevron listing its actions: provider results
+    /// with context actions, and desktop entries until pop-launcher says
+    /// they have none.
+    fn may_have_actions(&self, item: &SearchResult) -> bool {
+        match self.provider_items.get(&item.id) {
+            Some(provided) => !self.providers[provided.provider]
+                .context(&self.provider_context(), &provided.result)
+                .is_empty(),
+            None => self.is_desktop_entry(item) && !self.without_actions.contains(&item.id),
+        }
+    }
+
+    /// GPU Ctrl+Enter launches on, the one picked with Ctrl+G or else the
+    /// first non-default one.
+    fn launch_gpu(&self) -> Option<(usize, &GpuInfo)> {
+        match self.launch_gpu {
+            Some(idx) => self.gpus.get(idx).map(|gpu| (idx, gpu)),
+            None => self.gpus.iter().enumerate().find(|(_, gpu)| !gpu.default),
+        }
+    }
+
+    /// Window to focus instead of launching `entry`, following the launch policy.
+    fn running_instance(&self, entry: &DesktopEntryData) -> Option<ToplevelHandle> {
+        let mut windows = self
+            .toplevels
+            .iter()
+            .filter(|t| desktop_index::entry_matches(entry, &t.app_id));
+        match self.config.launch_policy {
+            LaunchPolicy::AlwaysLaunch => None,
+            // `toplevels` is in the order windows were opened
+            LaunchPolicy::FocusExisting => windows.next_back(),
+            LaunchPolicy::FocusIfSingle => windows.next().filter(|_| windows.next().is_none()),
+        }
+        .map(|t| t.handle.clone())
+    }
+
+    fn run_mode(&self) -> bool {
+        run_command::parse(&self.input_value).is_some()
+    }
+
+    /// Refreshes the run mode suggestions for the current input, listing
+    /// the executables in `PATH` first if needed.
+    fn update_run_suggestions(&mut self) -> Task<Message> {
+        self.run_focus = None;
+        let Some(input) = run_command::parse(&self.input_value) else {
+            self.run_suggestions.clear();
+            return Task::none();
+        };
+        let Some(executables) = &self.executables else {
+            self.executables = Some(Arc::default());
+            self.run_suggestions = run_command::suggestions(&self.run_history, &[], input.command);
+            return Task::perform(
+                async {
+                    tokio::task::spawn_blocking(run_command::path_executables)
+                        .await
+                        .unwrap_or_default()
+                },
+                |executables| cosmic::Action::App(Message::Executables(Arc::new(executables))),
+            );
+        };
+        self.run_suggestions =
+            run_command::suggestions(&self.run_history, executables, input.command);
+        Task::none()
+    }
+
+    /// Runs the selected suggestion or the typed command through the same
+    /// activation token path as desktop entries.
+    fn run_command(&mut self, gpu: GpuPreference) -> Task<Message> {
+        let Some(input) = run_command::parse(&self.input_value) else {
+            return Task::none();
+        };
+        let terminal = input.terminal;
+        let command = match self.run_focus.and_then(|i| self.run_suggestions.get(i)) {
+            Some(suggestion) => suggestion.clone(),
+            None => input.command.to_string(),
+        };
+        // Invalid commands are flagged in the view
+        let Some(program) = shlex::split(&command).and_then(|args| args.into_iter().next()) else {
+            return Task::none();
+        };
+        let save_history = self.run_history.push(&command);
+        let app_id = program.rsplit('/').next().unwrap_or(&program).to_string();
+        info!("Running {:?}", command);
+
+        Task::batch([
+            Task::perform(save_history, |()| cosmic::action::none()),
+            request_token(Some(String::from(Self::APP_ID)), Some(self.window_id)).map(move |token| {
+                cosmic::Action::App(Message::ActivationToken(
+                    token,
+                    app_id.clone(),
+                    command.clone(),
+                    gpu,
+                    terminal,
+                ))
+            }),
+        ])
+    }
+
+    fn collapse_actions(&mut self) {
+        self.expanded = None;
+        self.expanded_focus = None;
+        self.expand_requested = None;
+    }
+
+    fn provider_context(&self) -> ProviderContext<'_> {
+        ProviderContext {
+            toplevels: &self.toplevels,
+            desktop_index: &self.desktop_index,
+            app_id: Self::APP_ID,
+        }
+    }
+
+    /// Queries the providers with the current input and ranks their results
+    /// together with the last pop-launcher results. Runs on every keystroke,
+    /// so provider results show up before pop-launcher has answered.
+    fn update_results(&mut self) {
+        let query = self.input_value.trim();
+        let ctx = self.provider_context();
+        let provided = self
+            .providers
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, provider)| {
+                let results = provider.query(&ctx, query);
+                debug!("{} results from the {} provider", results.len(), provider.name());
+                results.into_iter().map(move |result| (idx, result))
+            })
+            .collect();
+        let drop_launcher_windows = self.providers.iter().any(|p| p.replaces_launcher_windows());
+        let (items, provider_items) =
+            providers::merge(self.launcher_results.clone(), provided, drop_launcher_windows);
+        self.launcher_items = items;
+        self.provider_items = provider_items;
+        if self.result_ids.len() < self.launcher_items.len() {
+            self.result_ids.extend(
+                (self.result_ids.len()..self.launcher_items.len()).map(|id| Id::new(id.to_string())),
+            );
+        }
+        self.focused = self.focused.min(self.launcher_items.len().saturating_sub(1));
+    }
+
+    fn perform_provider_action(&self, action: ProviderAction) {
+        let request = match action {
+            ProviderAction::ActivateToplevel(handle) => ToplevelRequest::Activate(handle),
+            ProviderAction::CloseToplevel(handle) => ToplevelRequest::Close(handle),
+        };
+        self.wayland_request(WaylandRequest::Toplevel(request));
+    }
+
+    /// Handle of the window selected in the switcher.
+    fn selected_toplevel(&self) -> Option<ToplevelHandle> {
+        let item = self.launcher_items.get(self.active?)?;
+        Some(self.toplevels.get(item.id as usize)?.handle.clone())
+    }
+
+    /// Rebuilds the switcher items after the toplevel list changed, keeping
+    /// `selected` selected wherever it ends up, see [`switcher::reselect`].
+    fn repopulate_switcher(&mut self, selected: Option<ToplevelHandle>) {
+        if self.overview_mode {
+            self.populate_overview();
+        } else {
+            self.populate_from_cached_toplevels();
+        }
+        let handles: Vec<_> = self
+            .launcher_items
+            .iter()
+            .map(|item| self.toplevels.get(item.id as usize).map(|t| &t.handle))
+            .collect();
+        let selected = selected.as_ref().map(Some);
+        self.active = switcher::reselect(&handles, selected.as_ref(), self.active);
+    }
+
+    /// Positions of the overview cards, scaled from the window geometry on
+    /// the overview output to fit `area`.
+    fn overview_layout(&self, area: Size) -> Vec<(usize, Rectangle)> {
+        let Some(output) = self
+            .launcher_items
+            .first()
+            .and_then(|item| self.toplevels.get(item.id as usize))
+            .and_then(|t| t.geometry())
+            .map(|(output, _)| output)
+        else {
+            return Vec::new();
+        };
+        let rects: Vec<_> = self
+            .launcher_items
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, item)| {
+                let geometry = self.toplevels.get(item.id as usize)?.geometry.get(output)?;
+                Some((
+                    idx,
+                    Rectangle::new(
+                        Point::new(geometry.x as f32, geometry.y as f32),
+                        Size::new(geometry.width.max(1) as f32, geometry.height.max(1) as f32),
+                    ),
+                ))
+            })
+            .collect();
+        let Some(bounds) = rects.iter().map(|(_, r)| *r).reduce(|a, b| a.union(&b)) else {
+            return Vec::new();
+        };
+
+        let scale = (area.width / bounds.width).min(area.height / bounds.height);
+        // Center the scaled layout in the available area
+        let offset = Point::new(
+            (area.width - bounds.width * scale) / 2.,
+            (area.height - bounds.height * scale) / 2.,
+        );
+        rects
+            .into_iter()
+            .map(|(idx, r)| {
+                let rect = Rectangle::new(
+                    Point::new(
+                        offset.x + (r.x - bounds.x) * scale,
+                        offset.y + (r.y - bounds.y) * scale,
+                    ),
+                    Size::new(r.width * scale, r.height * scale),
+                );
+                (idx, rect)
+            })
+            .collect()
+    }
+
+    /// Moves the overview selection to the closest card in `direction`.
+    fn navigate_overview(&mut self, direction: Direction) {
+        let layout = self.overview_layout(Size::new(1000., 1000.));
+        let center = |r: &Rectangle| Point::new(r.x + r.width / 2., r.y + r.height / 2.);
+        let Some(current) = layout
+            .iter()
+            .find(|(idx, _)| Some(*idx) == self.active)
+            .map(|(_, r)| center(r))
+        else {
+            self.active = layout.first().map(|(idx, _)| *idx);
+            return;
+        };
+
+        let next = layout
+            .iter()
+            .filter_map(|(idx, r)| {
+                let c = center(r);
+                let (dx, dy) = (c.x - current.x, c.y - current.y);
+                // Distance along the direction, and off to the side of it
+                let (along, across) = match direction {
+                    Direction::Left => (-dx, dy),
+                    Direction::Right => (dx, dy),
+                    Direction::Up => (-dy, dx),
+                    Direction::Down => (dy, dx),
+                };
+                (along > 0.).then_some((*idx, along + across.abs() * 2.))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((idx, _)) = next {
+            self.active = Some(idx);
+        }
+    }
+
+    fn wayland_request(&self, r: WaylandRequest) {
+        debug!("wayland request: {:?}", r);
+        if let Some(tx) = &self.wayland_tx {
+            if let Err(e) = tx.send(r) {
+                error!("Failed to send request to wayland thread: {e}");
+            }
+        } else {
+            error!("wayland thread not connected!");
+        }
+    }
+
+    /// Aborts the Alt+Tab switch and gives focus back to the window that was
+    /// active when the switcher opened, without activating the selection.
+    fn cancel_alt_tab(&mut self) -> Task<Message> {
+        if let Some(handle) = self.alt_tab_origin.take() {
+            if self.toplevels.iter().any(|t| t.handle == handle) {
+                info!("Alt+Tab cancelled - restoring focus to {:?}", handle);
+                self.wayland_request(WaylandRequest::Toplevel(ToplevelRequest::Activate(handle)));
+            }
+        }
+        self.hide()
+    }
+
+    /// Sends `request` for the toplevel behind an Alt+Tab item.
+    fn toplevel_item_request(&self, item: &SearchResult, request: fn(ToplevelHandle) -> ToplevelRequest) {
+        // Switcher items are indexes into `toplevels`, see `populate_from_cached_toplevels`
+        if let Some(toplevel) = self.toplevels.get(item.id as usize) {
+            self.wayland_request(WaylandRequest::Toplevel(request(toplevel.handle.clone())));
+        }
     }
 
     fn request(&self, r: launcher::Request) {
@@ -242,6 +986,8 @@ impl CosmicLauncher {
                 ..Default::default()
             }),
             overlap_notify(self.window_id, true),
+            self.refresh_desktop_index(),
+            self.refresh_gpus(),
         ];
 
         // Focus search input when showing in super launcher mode - delay it slightly
@@ -257,10 +1003,15 @@ impl CosmicLauncher {
 
 
     fn hide(&mut self) -> Task<Message> {
-        println!("DEBUG: hide() called - resetting state");
+        debug!("hide() called - resetting state");
         self.input_value.clear();
         self.focused = 0;
         self.active = None;
+        self.alt_tab_origin = None;
+        self.switcher_filter.clear();
+        self.collapse_actions();
+        self.launcher_results.clear();
+        self.provider_items.clear();
         self.set_mode(false, false); // Reset all modes
         self.search_debounce_timer = None; // Clear search debounce timer
         self.queue.clear();
@@ -270,7 +1021,7 @@ impl CosmicLauncher {
         let mut tasks = Vec::new();
 
         if self.surface_state == SurfaceState::Visible {
-            println!("DEBUG: Destroying layer surface");
+            debug!("Destroying layer surface");
             tasks.push(destroy_layer_surface(self.window_id));
             if self.menu.take().is_some() {
                 tasks.push(commands::popup::destroy_popup(*MENU_ID));
@@ -278,12 +1029,13 @@ impl CosmicLauncher {
         }
 
         self.surface_state = SurfaceState::Hidden;
-        println!("DEBUG: hide() complete - surface_state={:?}", self.surface_state);
+        debug!("hide() complete - surface_state={:?}", self.surface_state);
 
         Task::batch(tasks)
     }
 
     fn focus_next(&mut self) {
+        self.collapse_actions();
         if self.launcher_items.is_empty() {
             return;
         }
@@ -291,6 +1043,7 @@ impl CosmicLauncher {
     }
 
     fn focus_previous(&mut self) {
+        self.collapse_actions();
         if self.launcher_items.is_empty() {
             return;
         }
@@ -315,20 +1068,58 @@ impl CosmicLauncher {
         }
     }
 
+    fn card_layout(&self) -> CardLayout {
+        let grid_width = (self.width - 100.).clamp(400., 1300.);
+        // Two columns inside the grid padding, at most 600px per card
+        let card_width = ((grid_width - 40. - 10.) / 2.).min(600.);
+        let preview_width = (card_width * 220. / 600.).round();
+        CardLayout {
+            grid_width,
+            card_width,
+            preview: Size::new(preview_width, (preview_width * 125. / 220.).round()),
+        }
+    }
+
+    /// Tells the wayland thread the preview size so thumbnails are captured
+    /// at the resolution they are displayed at.
+    fn sync_thumbnail_size(&mut self) {
+        let preview = self.card_layout().preview;
+        let size = (preview.width as u32, preview.height as u32);
+        if self.wayland_tx.is_none() || self.thumbnail_size == Some(size) {
+            return;
+        }
+        self.thumbnail_size = Some(size);
+        self.wayland_request(WaylandRequest::ThumbnailSize {
+            width: size.0,
+            height: size.1,
+        });
+    }
+
     fn find_screenshot_for_item(&self, item: &SearchResult) -> Option<&WaylandImage> {
         info!("Looking for screenshot for item: '{}' (window: {:?})", item.name, item.window.is_some());
+
+        // Switcher items are indexes into `toplevels`
+        if self.alt_tab_mode {
+            return self
+                .toplevels
+                .get(item.id as usize)
+                .and_then(|t| self.thumbnails.get(&t.handle));
+        }
+        if let Some(provided) = self.provider_items.get(&item.id) {
+            return self.thumbnails.get(provided.result.window.as_ref()?);
+        }
         
         // If this launcher item represents a window, try to find matching screenshot
         if item.window.is_some() {
             // Try to match by window title/name with toplevels
-            for (handle, capture_image) in self.toplevel_captures.iter() {
-                // Find corresponding toplevel info
-                if let Some(toplevel_info) = self.toplevels.iter().find(|t| t.foreign_toplevel == *handle) {
-                    // Match by title (item.description often contains the window title for windows)
-                    if item.description.contains(&toplevel_info.title) 
-                        || toplevel_info.title.contains(&item.description)
-                        || item.name.contains(&toplevel_info.title)
-                        || toplevel_info.title.contains(&item.name) {
+            for toplevel_info in &self.toplevels {
+                // Match by title (item.description often contains the window title for windows)
+                if item.description.contains(&toplevel_info.title)
+                    || toplevel_info.title.contains(&item.description)
+                    || item.name.contains(&toplevel_info.title)
+                    || toplevel_info.title.contains(&item.name)
+                {
+                    if let Some(capture_image) = self.thumbnails.get(&toplevel_info.handle) {
                         info!("Match found! Using screenshot for: {}", item.name);
                         return Some(capture_image);
                     }
@@ -340,61 +1131,131 @@ impl CosmicLauncher {
     }
 
     fn handle_toplevel_update(&mut self, toplevel_update: ToplevelUpdate) {
+        let selected = self.selected_toplevel();
+        // Keep the time a window started asking for attention until it stops
+        match &toplevel_update {
+            ToplevelUpdate::Add(info) | ToplevelUpdate::Update(info) => {
+                if info.state.contains(&ToplevelState::Urgent) {
+                    self.urgent_since
+                        .entry(info.handle.clone())
+                        .or_insert_with(Instant::now);
+                } else {
+                    self.urgent_since.remove(&info.handle);
+                }
+            }
+            ToplevelUpdate::Remove(handle) => {
+                self.urgent_since.remove(handle);
+            }
+        }
         match toplevel_update {
             ToplevelUpdate::Add(info) => {
-                info!("New toplevel - title: '{}'", info.title);
+                info!("New toplevel - title: '{}' geometry: {:?}", info.title, info.geometry());
+                // The oldest launch of this app is done once it shows a window
+                if let Some(launch) = self.finish_launch_of(&info) {
+                    info!("{} opened its first window (token {:?})", launch.app_id, launch.token);
+                }
                 self.toplevels.push(info);
             }
             ToplevelUpdate::Update(info) => {
                 info!("Update toplevel - title: '{}'", info.title);
+                // Single instance apps raise the window they already have
+                if info.state.contains(&ToplevelState::Activated) {
+                    if let Some(launch) = self.finish_launch_of(&info) {
+                        info!("{} raised an existing window (token {:?})", launch.app_id, launch.token);
+                    }
+                }
                 if let Some(t) = self
                     .toplevels
                     .iter_mut()
-                    .find(|t| t.foreign_toplevel == info.foreign_toplevel)
+                    .find(|t| t.handle == info.handle)
                 {
                     *t = info;
                 }
             }
             ToplevelUpdate::Remove(handle) => {
                 info!("Close toplevel - handle: {:?}", handle);
-                self.toplevels.retain(|t| t.foreign_toplevel != handle);
+                self.toplevels.retain(|t| t.handle != handle);
+                self.thumbnails.remove(&handle);
             }
         }
+        if self.alt_tab_mode {
+            // Switcher items index into `toplevels` and copy their titles,
+            // rebuild them so the visible list stays current
+            self.repopulate_switcher(selected);
+        }
+        if self.super_launcher_mode {
+            // Provider results list windows, drop closed ones and show new ones
+            self.update_results();
+        }
     }
 }
 
+/// `text` with the chars at `positions` in bold accent color, for showing
+/// what a search matched.
+fn highlighted_text<'a>(text: &'a str, positions: &[usize], size: f32, focused: bool) -> Element<'a, Message> {
+    let accent = cosmic::iced::Color::from(cosmic::theme::active().cosmic().accent_text_color());
+    let spans: Vec<Span<'a>> = crate::fuzzy::runs(text, positions)
+        .into_iter()
+        .map(|(run, matched)| {
+            let run = cosmic::iced::widget::span(run);
+            if matched { run.font(cosmic::font::bold()).color(accent) } else { run }
+        })
+        .collect();
+    // Spans of one paragraph, so a long title wraps as a whole
+    let text = cosmic::iced::widget::rich_text(spans).size(size);
+    if focused {
+        text.class(cosmic::theme::Text::Accent).into()
+    } else {
+        text.into()
+    }
+}
+
+/// Switcher item for the toplevel at `idx` in `CosmicLauncher::toplevels`,
+/// described by its desktop entry when one was found.
+fn toplevel_item(idx: usize, toplevel: &Toplevel, app: Option<&AppEntry>) -> SearchResult {
+    let app_name = app.map_or(&toplevel.app_id, |app| &app.name);
+    SearchResult {
+        id: idx as u32, // Use index as simple ID
+        name: if !toplevel.title.is_empty() { toplevel.title.clone() } else if !app_name.is_empty() { app_name.clone() } else { "Unknown".to_string() },
+        description: app_name.clone(),
+        icon: None, // Will be determined in UI based on app_id
+        category_icon: None,
+        window: None, // We'll match screenshots by name/title instead
+    }
+}
+
+/// Starts `command`, failing early when its program cannot be found.
 async fn launch(
     token: Option<String>,
     app_id: String,
-    exec: String,
-    gpu: GpuPreference,
-    terminal: bool,
-) {
-    let mut envs = Vec::new();
-    if let Some(token) = token {
-        envs.push(("XDG_ACTIVATION_TOKEN".to_string(), token.clone()));
-        envs.push(("DESKTOP_STARTUP_ID".to_string(), token));
-    }
-
-    if let Some(gpu_envs) = try_get_gpu_envs(gpu).await {
-        envs.extend(gpu_envs);
+    command: LaunchCommand,
+    gpu_envs: Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    let envs = command.envs(token, gpu_envs);
+    let LaunchCommand { exec, terminal, .. } = command;
+    match shlex::split(&exec).and_then(|args| args.into_iter().next()) {
+        Some(program) if program_exists(&program) => {}
+        Some(program) => return Err(format!("{program} was not found")),
+        None => return Err(format!("Invalid command: {exec}")),
     }
 
     cosmic::desktop::spawn_desktop_exec(exec, envs, Some(&app_id), terminal).await;
+    Ok(())
 }
 
-async fn try_get_gpu_envs(gpu: GpuPreference) -> Option<HashMap<String, String>> {
-    let connection = zbus::Connection::system().await.ok()?;
-    let proxy = switcheroo_control::SwitcherooControlProxy::new(&connection)
-        .await
-        .ok()?;
-    let gpus = proxy.get_gpus().await.ok()?;
-    match gpu {
-        GpuPreference::Default => gpus.into_iter().find(|gpu| gpu.default),
-        GpuPreference::NonDefault => gpus.into_iter().find(|gpu| !gpu.default),
-        GpuPreference::SpecificIdx(idx) => gpus.into_iter().nth(idx as usize),
-    }
-    .map(|gpu| gpu.environment)
+/// Whether `program` is an executable path or can be found in `PATH`.
+fn program_exists(program: &str) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    let executable = |path: &std::path::Path| {
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    };
+    if program.contains('/') {
+        return executable(std::path::Path::new(program));
+    }
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| executable(&dir.join(program)))
+    })
 }
 
 impl cosmic::Application for CosmicLauncher {
@@ -410,38 +1271,72 @@ impl cosmic::Application for CosmicLauncher {
         let _conn = wayland_client::Connection::connect_to_env()
             .expect("Failed to connect to Wayland display");
 
-        (
-            CosmicLauncher {
-                core,
-                input_value: String::new(),
-                surface_state: SurfaceState::Hidden,
-                launcher_items: Vec::new(),
-                tx: None,
-                menu: None,
-                cursor_position: None,
-                focused: 0,
-                last_hide: Instant::now(),
-                alt_tab_mode: false,
-                super_launcher_mode: false,
-                window_id: window::Id::unique(),
-                queue: VecDeque::new(),
-                result_ids: (0..10)
-                    .map(|id| Id::new(id.to_string()))
-                    .collect::<Vec<_>>(),
-                margin: 0.,
-                overlap: HashMap::new(),
-                height: 100.,
-                needs_clear: false,
-
-                toplevel_captures: HashMap::new(),
-                screenshot_cache_time: HashMap::new(),
-                toplevels: Vec::new(),
-                active: None,
-                backend_event_receiver: None,
-                search_debounce_timer: None,
-            },
-            Task::none(),
-        )
+        let mut launcher = CosmicLauncher {
+            core,
+            input_value: String::new(),
+            surface_state: SurfaceState::Hidden,
+            launcher_items: Vec::new(),
+            launcher_results: Vec::new(),
+            providers: vec![Box::new(ToplevelProvider)],
+            provider_items: HashMap::new(),
+            tx: None,
+            menu: None,
+            cursor_position: None,
+            focused: 0,
+            last_hide: Instant::now(),
+            alt_tab_mode: false,
+            super_launcher_mode: false,
+            overview_mode: false,
+            workspace_mode: false,
+            window_id: window::Id::unique(),
+            queue: VecDeque::new(),
+            result_ids: (0..10)
+                .map(|id| Id::new(id.to_string()))
+                .collect::<Vec<_>>(),
+            margin: 0.,
+            overlap: HashMap::new(),
+            height: 100.,
+            width: 1400.,
+            needs_clear: false,
+
+            thumbnails: ThumbnailCache::new(thumbnail_cache::DEFAULT_BUDGET),
+            toplevels: Vec::new(),
+            workspaces: Vec::new(),
+            workspace_thumbnails: ThumbnailCache::new(thumbnail_cache::DEFAULT_BUDGET),
+            active: None,
+            alt_tab_origin: None,
+            switcher_filter: String::new(),
+            urgent_since: HashMap::new(),
+            desktop_index: Arc::default(),
+            desktop_index_loading: false,
+            config: LauncherConfig::load(Self::APP_ID),
+            force_new_instance: false,
+            pending_launches: Vec::new(),
+            gpus: Vec::new(),
+            gpus_loading: false,
+            launch_gpu: None,
+            gpu_override: None,
+            expanded: None,
+            expanded_focus: None,
+            expand_requested: None,
+            without_actions: HashSet::new(),
+            run_history: run_command::History::load(),
+            executables: None,
+            run_suggestions: Vec::new(),
+            run_focus: None,
+            next_launch_id: 0,
+            wayland_tx: None,
+            thumbnail_size: None,
+            capabilities: Capabilities::default(),
+            backend_event_receiver: None,
+            search_debounce_timer: None,
+        };
+        // Load app names and icons before the switcher is first shown
+        let task = Task::batch([
+            launcher.refresh_desktop_index(),
+            launcher.refresh_gpus(),
+        ]);
+        (launcher, task)
     }
 
     fn core(&self) -> &Core {
@@ -458,6 +1353,13 @@ impl cosmic::Application for CosmicLauncher {
             Message::InputChanged(value) => {
                 // Always update input value immediately for responsive UI
                 self.input_value.clone_from(&value);
+                if run_command::parse(&value).is_some() {
+                    return self.update_run_suggestions();
+                }
+                if self.super_launcher_mode {
+                    self.collapse_actions();
+                    self.update_results();
+                }
                 
                 // Use minimal debounce for responsive search
                 // For short queries (1-2 chars), search immediately
@@ -476,6 +1378,13 @@ impl cosmic::Application for CosmicLauncher {
             Message::Backspace => {
                 // Always update input value immediately for responsive UI
                 self.input_value.pop();
+                if run_command::parse(&self.input_value).is_some() {
+                    return self.update_run_suggestions();
+                }
+                if self.super_launcher_mode {
+                    self.collapse_actions();
+                    self.update_results();
+                }
                 
                 // Use minimal debounce for responsive search
                 let input_len = self.input_value.len();
@@ -508,10 +1417,42 @@ impl cosmic::Application for CosmicLauncher {
                     return self.update(Message::Activate(Some(i)));
                 }
             }
+            Message::Activate(idx) if self.workspace_mode => {
+                if idx.is_some() {
+                    self.active = idx;
+                }
+                return self.activate_selected_workspace();
+            }
+            Message::Activate(None) if self.super_launcher_mode && self.run_mode() => {
+                return self.run_command(GpuPreference::Default);
+            }
+            Message::Activate(None) if self.super_launcher_mode && self.expanded_focus.is_some() => {
+                if let Some((id, options)) = &self.expanded {
+                    if let Some(option) = self.expanded_focus.and_then(|i| options.get(i)) {
+                        return self.update(Message::ActivateAction(*id, option.id));
+                    }
+                }
+            }
             Message::Activate(idx) => {
+                // Enter activates the selected window in the switcher, or the
+                // focused search result in the launcher
+                let idx = idx
+                    .or(self.active.filter(|_| self.alt_tab_mode))
+                    .or(Some(self.focused).filter(|_| self.super_launcher_mode));
                 if let Some(idx) = idx {
                     if let Some(item) = self.launcher_items.get(idx) {
-                        self.request(launcher::Request::Activate(item.id));
+                        if self.alt_tab_mode {
+                            self.toplevel_item_request(item, ToplevelRequest::Activate);
+                        } else if let Some(provided) = self.provider_items.get(&item.id) {
+                            let provider = &self.providers[provided.provider];
+                            if let Some(action) = provider.activate(&self.provider_context(), &provided.result) {
+                                self.perform_provider_action(action);
+                            }
+                        } else {
+                            self.force_new_instance = false;
+                            self.gpu_override = None;
+                            self.request(launcher::Request::Activate(item.id));
+                        }
                         return self.hide();
                     }
                 }
@@ -528,6 +1469,16 @@ impl cosmic::Application for CosmicLauncher {
                     self.tx = None;
                 }
                 launcher::Event::Response(res) => match res {
+                    pop_launcher::Response::Context { id, options } if self.expand_requested == Some(id) => {
+                        self.expand_requested = None;
+                        debug!("{} actions for result {}", options.len(), id);
+                        if options.is_empty() {
+                            // Hides the chevron, there is nothing to expand
+                            self.without_actions.insert(id);
+                        }
+                        self.expanded_focus = (!options.is_empty()).then_some(0);
+                        self.expanded = Some((id, options));
+                    }
                     pop_launcher::Response::Context { id, options } => {
                         self.menu = Some((id, options));
                         if let Some(cursor_position) = self.cursor_position {
@@ -563,6 +1514,20 @@ impl cosmic::Application for CosmicLauncher {
                         action_name,
                     } => {
                         if let Some(entry) = cosmic::desktop::load_desktop_file(&[], path) {
+                            // Launching on a chosen GPU always starts a new instance
+                            let gpu_override = self.gpu_override.take();
+                            let gpu_preference = gpu_override.unwrap_or(gpu_preference);
+                            let force_new_instance = std::mem::take(&mut self.force_new_instance)
+                                || gpu_override.is_some();
+                            if action_name.is_none() && !force_new_instance {
+                                if let Some(handle) = self.running_instance(&entry) {
+                                    info!("Focusing running instance of {}", entry.id);
+                                    self.wayland_request(WaylandRequest::Toplevel(
+                                        ToplevelRequest::Activate(handle),
+                                    ));
+                                    return self.hide();
+                                }
+                            }
                             let exec = if let Some(action_name) = action_name {
                                 entry
                                     .desktop_actions
@@ -593,17 +1558,24 @@ impl cosmic::Application for CosmicLauncher {
                     }
                     pop_launcher::Response::Update(mut list) => {
                         info!("Received launcher response with {} items", list.len());
+                        // Result ids are only valid for the search that produced them
+                        self.collapse_actions();
+                        self.without_actions.clear();
                         
                         if self.input_value.is_empty() {
                             list.reverse();
                         }
-                        list.sort_by(|a, b| {
-                            let a = i32::from(a.window.is_none());
-                            let b = i32::from(b.window.is_none());
-                            a.cmp(&b)
-                        });
-
-                        self.launcher_items.splice(.., list);
+                        if self.super_launcher_mode {
+                            self.launcher_results = list;
+                            self.update_results();
+                        } else {
+                            list.sort_by(|a, b| {
+                                let a = i32::from(a.window.is_none());
+                                let b = i32::from(b.window.is_none());
+                                a.cmp(&b)
+                            });
+                            self.launcher_items.splice(.., list);
+                        }
                         if self.result_ids.len() < self.launcher_items.len() {
                             self.result_ids.extend(
                                 (self.result_ids.len()..self.launcher_items.len())
@@ -619,12 +1591,12 @@ impl cosmic::Application for CosmicLauncher {
                                 // Adjust the active index if it's beyond the list size
                                 if current_active >= self.launcher_items.len() {
                                     self.active = Some(0);
-                                    println!("DEBUG: Adjusted selection to window 0 (only {} items)", self.launcher_items.len());
+                                    debug!("Adjusted selection to window 0 (only {} items)", self.launcher_items.len());
                                 }
                             } else {
                                 // Default to first window if no active selection
                                 self.active = Some(0);
-                                println!("DEBUG: Setting initial selection to window 0");
+                                debug!("Setting initial selection to window 0");
                             }
                         }
                         let mut cmds = Vec::new();
@@ -659,14 +1631,14 @@ impl cosmic::Application for CosmicLauncher {
             }
             Message::Layer(e) => match e {
                 LayerEvent::Focused | LayerEvent::Done => {
-                    println!("DEBUG: Layer event: {:?}", e);
+                    debug!("Layer event: {:?}", e);
                 }
                 LayerEvent::Unfocused => {
                     // In Alt+Tab mode, don't hide on unfocus - wait for Alt release
                     if self.alt_tab_mode {
-                        println!("DEBUG: Layer unfocused in Alt+Tab mode - staying visible");
+                        debug!("Layer unfocused in Alt+Tab mode - staying visible");
                     } else {
-                        println!("DEBUG: Layer unfocused - hiding launcher");
+                        debug!("Layer unfocused - hiding launcher");
                         self.last_hide = Instant::now();
                         return self.hide();
                     }
@@ -701,6 +1673,239 @@ impl cosmic::Application for CosmicLauncher {
                 }
                 return self.hide();
             }
+            Message::Cancel => {
+                if self.menu.take().is_some() {
+                    return commands::popup::destroy_popup(*MENU_ID);
+                }
+                if self.alt_tab_mode {
+                    return self.cancel_alt_tab();
+                }
+                return self.hide();
+            }
+            Message::Select(idx) => {
+                let len = if self.workspace_mode {
+                    self.workspaces.len()
+                } else {
+                    self.launcher_items.len()
+                };
+                if (self.alt_tab_mode || self.workspace_mode) && idx < len {
+                    self.active = Some(idx);
+                }
+            }
+            Message::SwitcherType(c) => {
+                // Leading whitespace would start a filter matching every window
+                let leading_space = !self.switcher_filtering() && c.trim().is_empty();
+                if self.alt_tab_mode && !self.overview_mode && !leading_space {
+                    self.switcher_filter.push_str(&c);
+                    self.filter_switcher();
+                }
+            }
+            Message::SwitcherCharacter(c, typed, alt, shift) => {
+                // Elsewhere the key is only typed into the search input
+                if !self.alt_tab_mode {
+                    return Task::none();
+                }
+                return match switcher::character_action(&c, alt, shift, self.switcher_filtering()) {
+                    switcher::CharacterAction::Type => self.update(Message::SwitcherType(typed)),
+                    switcher::CharacterAction::JumpToUrgent => self.update(Message::JumpToUrgent),
+                    switcher::CharacterAction::MoveToWorkspace(n) => {
+                        self.update(Message::MoveSelected(MoveTarget::Workspace(n)))
+                    }
+                };
+            }
+            Message::SwitcherBackspace => {
+                if self.alt_tab_mode && !self.overview_mode && self.switcher_filter.pop().is_some() {
+                    self.filter_switcher();
+                }
+            }
+            Message::Navigate(direction) => {
+                if self.workspace_mode {
+                    match direction {
+                        Direction::Left | Direction::Up => self.select_workspace(-1),
+                        Direction::Right | Direction::Down => self.select_workspace(1),
+                    }
+                } else if self.overview_mode {
+                    self.navigate_overview(direction);
+                } else if self.run_mode() {
+                    // Arrows pick a suggestion, none selected runs the input
+                    let len = self.run_suggestions.len();
+                    self.run_focus = match (direction, self.run_focus) {
+                        (Direction::Down, None) if len > 0 => Some(0),
+                        (Direction::Down, Some(i)) => Some((i + 1).min(len.saturating_sub(1))),
+                        (Direction::Up, Some(0)) => None,
+                        (Direction::Up, Some(i)) => Some(i - 1),
+                        (_, focus) => focus,
+                    };
+                } else if let (Some(focus), Some((_, options))) = (self.expanded_focus, &self.expanded) {
+                    // Arrows move through the expanded actions
+                    match direction {
+                        Direction::Up => self.expanded_focus = Some(focus.saturating_sub(1)),
+                        Direction::Down => {
+                            self.expanded_focus = Some((focus + 1).min(options.len() - 1));
+                        }
+                        Direction::Left => self.collapse_actions(),
+                        Direction::Right => {}
+                    }
+                } else {
+                    match direction {
+                        Direction::Up => {
+                            return self.update(Message::KeyboardNav(keyboard_nav::Action::FocusPrevious));
+                        }
+                        Direction::Down => {
+                            return self.update(Message::KeyboardNav(keyboard_nav::Action::FocusNext));
+                        }
+                        Direction::Right if self.super_launcher_mode => {
+                            return self.update(Message::ExpandActions(self.focused));
+                        }
+                        Direction::Left => self.collapse_actions(),
+                        Direction::Right => {}
+                    }
+                }
+            }
+            Message::ShiftActivate => {
+                if self.workspace_mode {
+                    let workspace = self.active.and_then(|idx| self.workspaces.get(idx));
+                    if let (Some(window), Some(workspace)) = (&self.alt_tab_origin, workspace) {
+                        self.wayland_request(WaylandRequest::Toplevel(
+                            ToplevelRequest::MoveToWorkspace(window.clone(), workspace.handle.clone()),
+                        ));
+                    }
+                    return self.hide();
+                }
+                if self.super_launcher_mode {
+                    let task = self.update(Message::Activate(None));
+                    // Read when pop-launcher answers with the desktop entry
+                    self.force_new_instance = true;
+                    return task;
+                }
+            }
+            Message::GpuActivate => {
+                if self.super_launcher_mode {
+                    let gpu = match self.launch_gpu() {
+                        Some((idx, _)) => GpuPreference::SpecificIdx(idx as u32),
+                        None => GpuPreference::NonDefault,
+                    };
+                    if self.run_mode() {
+                        return self.run_command(gpu);
+                    }
+                    let desktop_entry = self
+                        .launcher_items
+                        .get(self.focused)
+                        .is_some_and(|item| self.is_desktop_entry(item));
+                    let task = self.update(Message::Activate(None));
+                    if desktop_entry {
+                        // Read when pop-launcher answers with the desktop entry
+                        self.gpu_override = Some(gpu);
+                    }
+                    return task;
+                }
+            }
+            Message::CycleGpu => {
+                if self.gpus.len() > 1 {
+                    let next = self.launch_gpu().map_or(0, |(idx, _)| idx + 1) % self.gpus.len();
+                    self.launch_gpu = Some(next);
+                }
+            }
+            Message::Gpus(gpus) => {
+                self.gpus_loading = false;
+                info!("Found {} GPUs", gpus.len());
+                self.launch_gpu = self.launch_gpu.filter(|idx| *idx < gpus.len());
+                self.gpus = gpus;
+            }
+            Message::ExpandActions(idx) => {
+                if let Some(item) = self
+                    .launcher_items
+                    .get(idx)
+                    .filter(|item| self.may_have_actions(item))
+                {
+                    self.focused = idx;
+                    if self.expanded.as_ref().is_some_and(|(id, _)| *id == item.id) {
+                        self.collapse_actions();
+                    } else if let Some(provided) = self.provider_items.get(&item.id) {
+                        // Provider actions are known right away
+                        let provider = &self.providers[provided.provider];
+                        let options = provider.context(&self.provider_context(), &provided.result);
+                        let id = item.id;
+                        self.collapse_actions();
+                        self.expanded_focus = (!options.is_empty()).then_some(0);
+                        self.expanded = Some((id, options));
+                    } else {
+                        self.collapse_actions();
+                        self.expand_requested = Some(item.id);
+                        self.request(launcher::Request::Context(item.id));
+                    }
+                }
+            }
+            Message::ActivateAction(id, context) => {
+                if let Some(provided) = self.provider_items.get(&id) {
+                    let provider = &self.providers[provided.provider];
+                    let ctx = self.provider_context();
+                    if let Some(action) = provider.activate_context(&ctx, &provided.result, context) {
+                        self.perform_provider_action(action);
+                    }
+                    return self.hide();
+                }
+                // pop-launcher answers with the action's desktop entry, which
+                // is launched like the main entry
+                self.force_new_instance = false;
+                self.gpu_override = None;
+                self.request(launcher::Request::ActivateContext(id, context));
+                return self.hide();
+            }
+            Message::Executables(executables) => {
+                self.executables = Some(executables);
+                if self.run_mode() {
+                    return self.update_run_suggestions();
+                }
+            }
+            Message::Config(config) => {
+                debug!("Config changed: {:?}", config);
+                self.config = config;
+            }
+            Message::JumpToUrgent => {
+                return self.jump_to_urgent();
+            }
+            Message::DesktopIndex(index) => {
+                self.desktop_index_loading = false;
+                if let Some(index) = index {
+                    self.desktop_index = index;
+                    if self.alt_tab_mode {
+                        self.repopulate_switcher(self.selected_toplevel());
+                    }
+                }
+            }
+            Message::MoveSelected(target) => {
+                if self.alt_tab_mode {
+                    self.move_selected(target);
+                }
+            }
+            Message::CloseWindow => {
+                if self.alt_tab_mode {
+                    if let Some(item) = self.launcher_items.get(self.active.unwrap_or(0)) {
+                        self.toplevel_item_request(item, ToplevelRequest::Close);
+                    }
+                }
+            }
+            Message::KeyboardNav(keyboard_nav::Action::FocusNext) if self.run_mode() => {
+                // Tab completes the input with the selected or first suggestion
+                let prefix = if run_command::parse(&self.input_value).is_some_and(|r| r.terminal) {
+                    "!"
+                } else {
+                    ">"
+                };
+                if let Some(suggestion) = self
+                    .run_suggestions
+                    .get(self.run_focus.unwrap_or(0))
+                {
+                    self.input_value = format!("{prefix}{suggestion}");
+                    return self.update_run_suggestions();
+                }
+            }
+            Message::KeyboardNav(e) if self.workspace_mode => match e {
+                keyboard_nav::Action::FocusNext => self.select_workspace(1),
+                keyboard_nav::Action::FocusPrevious => self.select_workspace(-1),
+                _ => {}
+            },
             Message::KeyboardNav(e) => {
                 match e {
                     keyboard_nav::Action::FocusNext => {
@@ -736,9 +1941,76 @@ impl cosmic::Application for CosmicLauncher {
                 };
             }
             Message::ActivationToken(token, app_id, exec, dgpu, terminal) => {
-                return Task::perform(launch(token, app_id, exec, dgpu, terminal), |()| {
-                    cosmic::action::app(Message::Hide)
+                let gpu_envs = self.gpu_envs(dgpu);
+                let command = self.config.command(&app_id, &exec, terminal);
+                let id = self.next_launch_id;
+                self.next_launch_id += 1;
+                info!("Launching {} (token {:?})", app_id, token);
+                // Terminal apps and commands without a desktop entry may never
+                // open a window of their own, and without the toplevel list
+                // there is no telling when a window appears. A running single
+                // instance app may only raise the window it has.
+                let expect_window = !terminal
+                    && self.desktop_index.lookup(&app_id).is_some()
+                    && self.capabilities.toplevel_tracking()
+                    && !self.toplevels.iter().any(|toplevel| self.app_matches(&app_id, toplevel));
+                self.pending_launches.push(PendingLaunch {
+                    id,
+                    token: token.clone(),
+                    app_id: app_id.clone(),
+                    expect_window,
                 });
+                let mut tasks = vec![Task::perform(launch(token, app_id, command, gpu_envs), move |res| {
+                    cosmic::action::app(Message::Launched(id, res))
+                })];
+                if expect_window {
+                    tasks.push(Task::perform(tokio::time::sleep(LAUNCH_TIMEOUT), move |()| {
+                        cosmic::action::app(Message::LaunchTimeout(id))
+                    }));
+                }
+                return Task::batch(tasks);
+            }
+            Message::Launched(id, res) => {
+                if let Err(err) = res {
+                    if let Some(launch) = self.finish_launch(id) {
+                        error!("Failed to launch {}: {}", launch.app_id, err);
+                        let notification = Notification {
+                            icon: "dialog-error".into(),
+                            summary: format!("Failed to start {}", self.app_name(&launch.app_id)),
+                            body: err,
+                        };
+                        return Task::batch([
+                            Task::perform(notifications::send_session(notification), |()| {
+                                cosmic::action::none()
+                            }),
+                            self.update(Message::Hide),
+                        ]);
+                    }
+                } else if self
+                    .pending_launches
+                    .iter()
+                    .any(|launch| launch.id == id && !launch.expect_window)
+                {
+                    // Nothing left to wait for
+                    self.finish_launch(id);
+                }
+                return self.update(Message::Hide);
+            }
+            Message::LaunchTimeout(id) => {
+                if let Some(launch) = self.finish_launch(id) {
+                    warn!("{} opened no window within {:?}", launch.app_id, LAUNCH_TIMEOUT);
+                    let notification = Notification {
+                        icon: "dialog-warning".into(),
+                        summary: format!("No window appeared for {}", self.app_name(&launch.app_id)),
+                        body: format!(
+                            "It was started {} seconds ago. It may still be loading or run without a window.",
+                            LAUNCH_TIMEOUT.as_secs()
+                        ),
+                    };
+                    return Task::perform(notifications::send_session(notification), |()| {
+                        cosmic::action::none()
+                    });
+                }
             }
 
             Message::AltTab => {
@@ -747,7 +2019,7 @@ impl cosmic::Application for CosmicLauncher {
                     let current = self.active.unwrap_or(0);
                     let next = (current + 1) % self.launcher_items.len();
                     self.active = Some(next);
-                    println!("DEBUG: AltTab - cycling to {} (of {})", next, self.launcher_items.len());
+                    debug!("AltTab - cycling to {} (of {})", next, self.launcher_items.len());
                 } else {
                     self.active = Some(0);
                 }
@@ -762,23 +2034,28 @@ impl cosmic::Application for CosmicLauncher {
                         current - 1
                     };
                     self.active = Some(prev);
-                    println!("DEBUG: ShiftAltTab - cycling to {} (of {})", prev, self.launcher_items.len());
+                    debug!("ShiftAltTab - cycling to {} (of {})", prev, self.launcher_items.len());
                 } else {
                     self.active = Some(0);
                 }
             }
             Message::AltRelease => {
                 // On Alt release, activate the currently selected window and hide
-                if self.alt_tab_mode {
+                // A filter being typed keeps the switcher open until Enter
+                if self.alt_tab_mode && !self.overview_mode && !self.switcher_filtering() {
                     let selected_index = self.active.unwrap_or(0);
-                    println!("DEBUG: Alt released - activating window at index {} then hiding", selected_index);
+                    debug!("Alt released - activating window at index {} then hiding", selected_index);
                     if let Some(item) = self.launcher_items.get(selected_index) {
-                        self.request(launcher::Request::Activate(item.id));
+                        self.toplevel_item_request(item, ToplevelRequest::Activate);
                     }
                     return self.hide();
                 }
             }
             Message::SuperRelease => {
+                // Releasing Super after Super+Tab switches to the selected workspace
+                if self.workspace_mode {
+                    return self.activate_selected_workspace();
+                }
                 // On Super release in super launcher mode, hide the launcher
                 if self.super_launcher_mode {
                     return self.hide();
@@ -786,21 +2063,74 @@ impl cosmic::Application for CosmicLauncher {
             }
             Message::Opened(size, _id) => {
                 self.height = size.height;
+                self.width = size.width;
                 self.handle_overlap();
+                self.sync_thumbnail_size();
             }
             Message::BackendEvent(event) => match event {
                 WaylandUpdate::Toplevel(toplevel_update) => {
                     self.handle_toplevel_update(toplevel_update);
                 }
                 WaylandUpdate::Image(handle, wayland_image) => {
+                    // Late capture of a toplevel that has already been closed
+                    if !self.toplevels.iter().any(|t| t.handle == handle) {
+                        return Task::none();
+                    }
                     info!("Storing screenshot for toplevel: {:?}", handle);
-                    self.toplevel_captures.insert(handle.clone(), wayland_image);
-                    self.screenshot_cache_time.insert(handle, Instant::now());
+                    self.thumbnails.insert(handle, wayland_image);
+                    debug!("Thumbnail cache holds {} bytes", self.thumbnails.bytes());
+                }
+                WaylandUpdate::CaptureFailed(handle, reason) => {
+                    // Keep showing the previous thumbnail or the app icon
+                    debug!("No thumbnail for {:?}: {}", handle, reason);
+                }
+                WaylandUpdate::Workspaces(workspaces) => {
+                    self.workspace_thumbnails.retain(|handle| {
+                        workspaces.iter().any(|w| &w.handle == handle)
+                    });
+                    self.workspaces = workspaces;
+                    if self.workspace_mode {
+                        self.active = self
+                            .active
+                            .map(|i| i.min(self.workspaces.len().saturating_sub(1)));
+                    }
+                }
+                WaylandUpdate::WorkspaceImage(handle, wayland_image) => {
+                    if self.workspaces.iter().any(|w| w.handle == handle) {
+                        self.workspace_thumbnails.insert(handle, wayland_image);
+                    }
+                }
+                WaylandUpdate::Init(tx) => {
+                    // Handles from a previous connection are dead, the new
+                    // handler announces every toplevel again.
+                    self.toplevels.clear();
+                    self.thumbnails.clear();
+                    self.workspaces.clear();
+                    self.workspace_thumbnails.clear();
+                    self.alt_tab_origin = None;
+                    self.urgent_since.clear();
+                    self.wayland_tx = Some(tx);
+                    self.thumbnail_size = None;
+                    self.sync_thumbnail_size();
+                }
+                WaylandUpdate::Capabilities(capabilities) => {
+                    if !capabilities.thumbnails() {
+                        info!("Window thumbnails unavailable, using app icons");
+                    }
+                    self.capabilities = capabilities;
+                }
+                WaylandUpdate::Error(err) => {
+                    error!("Wayland handler error: {}", err);
+                }
+                WaylandUpdate::Finished => {
+                    self.wayland_tx = None;
                 }
-                WaylandUpdate::Init => {}
-                WaylandUpdate::Finished => {}
             }
             Message::DebouncedSearch(search_term) => {
+                // Commands are completed locally, not searched
+                if run_command::parse(&search_term).is_some() {
+                    return Task::none();
+                }
                 // Only perform search if this is the most recent debounce timer
                 if let Some(timer) = self.search_debounce_timer {
                     // Reduced threshold from 250ms to 40ms for more responsiveness
@@ -832,16 +2162,53 @@ impl cosmic::Application for CosmicLauncher {
                 }
             }
             Details::ActivateAction { action, .. } => {
-                println!("DEBUG: ActivateAction {}", action);
+                debug!("ActivateAction {}", action);
 
                 let Ok(cmd) = LauncherTasks::from_str(&action) else {
                     return Task::none();
                 };
+                if matches!(cmd, LauncherTasks::Diagnose) {
+                    return Task::none();
+                }
+                if matches!(cmd, LauncherTasks::Urgent) {
+                    return self.jump_to_urgent();
+                }
+
+                // Remember which window had focus so Escape can restore it
+                if self.surface_state == SurfaceState::Hidden {
+                    self.alt_tab_origin = self
+                        .toplevels
+                        .iter()
+                        .find(|t| t.state.contains(&ToplevelState::Activated))
+                        .map(|t| t.handle.clone());
+                }
+
+                if matches!(cmd, LauncherTasks::Workspaces) {
+                    return self.open_workspaces();
+                }
 
                 self.set_mode(true, false); // Alt+Tab mode only
                 
                 // Use cached toplevels immediately for instant display
-                self.populate_from_cached_toplevels();
+                if matches!(cmd, LauncherTasks::Overview) {
+                    self.overview_mode = true;
+                    self.populate_overview();
+                    // Start on the focused window
+                    self.active = self
+                        .launcher_items
+                        .iter()
+                        .position(|item| {
+                            self.toplevels
+                                .get(item.id as usize)
+                                .is_some_and(|t| t.state.contains(&ToplevelState::Activated))
+                        })
+                        .or((!self.launcher_items.is_empty()).then_some(0));
+                } else {
+                    self.populate_from_cached_toplevels();
+                }
+                if self.surface_state == SurfaceState::Hidden {
+                    self.refresh_stale_thumbnails();
+                }
                 
                 // For Alt+Tab, we don't need search request - we have cached data
                 // Fresh screenshots will come from wayland subscription
@@ -850,6 +2217,10 @@ impl cosmic::Application for CosmicLauncher {
                 let update_task = match cmd {
                     LauncherTasks::AltTab => self.update(Message::AltTab),
                     LauncherTasks::ShiftAltTab => self.update(Message::ShiftAltTab),
+                    LauncherTasks::Overview
+                    | LauncherTasks::Workspaces
+                    | LauncherTasks::Urgent
+                    | LauncherTasks::Diagnose => Task::none(),
                 };
                 return Task::batch(vec![show_task, update_task]);
             }
@@ -867,7 +2238,7 @@ impl cosmic::Application for CosmicLauncher {
         if id == self.window_id {
             // Don't render if surface should be hidden
             if self.surface_state == SurfaceState::Hidden {
-                println!("DEBUG: view_window called but surface is Hidden - returning empty");
+                debug!("view_window called but surface is Hidden - returning empty");
                 return container(text(""))
                     .width(Length::Fixed(1.0))
                     .height(Length::Fixed(1.0))
@@ -882,7 +2253,11 @@ impl cosmic::Application for CosmicLauncher {
                     .into();
             }
             // Show appropriate view based on mode
-            if self.alt_tab_mode {
+            if self.workspace_mode {
+                self.view_workspaces()
+            } else if self.overview_mode && !self.launcher_items.is_empty() {
+                self.view_overview()
+            } else if self.alt_tab_mode {
                 // Alt+Tab mode: Window switching with thumbnails
                 self.view_alt_tab()
             } else {
@@ -900,6 +2275,9 @@ impl cosmic::Application for CosmicLauncher {
     fn subscription(&self) -> Subscription<Self::Message> {
         Subscription::batch(vec![
             wayland_subscription().map(Message::BackendEvent),
+            self.core
+                .watch_config::<LauncherConfig>(Self::APP_ID)
+                .map(|update| Message::Config(update.config)),
             launcher::subscription(0).map(Message::LauncherEvent),
             listen_raw(|e, _status, id| match e {
                 cosmic::iced::Event::PlatformSpecific(PlatformSpecific::Wayland(
@@ -912,49 +2290,68 @@ impl cosmic::Application for CosmicLauncher {
                     key,
                     ..
                 }) => {
-                    println!("DEBUG: Key released: {:?}", key);
+                    debug!("Key released: {:?}", key);
                     match key {
                         Key::Named(Named::Alt) => {
                             // Alt released - send message to let app decide what to do
-                            println!("DEBUG: Alt key released");
+                            debug!("Alt key released");
                             return Some(Message::AltRelease);
                         }
                         Key::Named(Named::Super) => {
                             // Super released - send message to let app decide what to do
-                            println!("DEBUG: Super key released");
+                            debug!("Super key released");
                             return Some(Message::SuperRelease);
                         }
                         _ => {}
                     }
                     None
                 },
-                cosmic::iced::Event::Keyboard(iced::keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                cosmic::iced::Event::Keyboard(iced::keyboard::Event::KeyPressed { key, modified_key, modifiers, .. }) => {
                     // Debug: Log ALL key presses to understand what's happening
-                    println!("DEBUG: Key pressed: {:?}, modifiers: alt={}, shift={}, ctrl={}", key, modifiers.alt(), modifiers.shift(), modifiers.control());
+                    debug!("Key pressed: {:?}, modifiers: alt={}, shift={}, ctrl={}", key, modifiers.alt(), modifiers.shift(), modifiers.control());
                     
                     // Killswitch: Ctrl+Alt+J to exit
                     if let Key::Character(c) = &key {
                         if c == "j" && modifiers.control() && modifiers.alt() {
-                            println!("DEBUG: Killswitch activated - exiting");
+                            debug!("Killswitch activated - exiting");
                             std::process::exit(0);
                         }
                     }
 
                     // Handle Alt+Tab and Shift+Alt+Tab explicitly - but only when UI is visible
                     if let Key::Named(Named::Tab) = key {
-                        println!("DEBUG: Raw Tab event: alt={}, shift={}", modifiers.alt(), modifiers.shift());
+                        debug!("Raw Tab event: alt={}, shift={}", modifiers.alt(), modifiers.shift());
                         // Only handle Tab navigation when launcher UI might be visible
                         // We can't access self.surface_state here, so we'll handle this in the message update
                         if modifiers.alt() && modifiers.shift() {
-                            println!("DEBUG: Raw Shift+Alt+Tab");
+                            debug!("Raw Shift+Alt+Tab");
                             return Some(Message::ShiftAltTab);
                         } else if modifiers.alt() {
-                            println!("DEBUG: Raw Alt+Tab");
+                            debug!("Raw Alt+Tab");
                             return Some(Message::AltTab);
                         }
-                        println!("DEBUG: Raw Tab - focusing next");
+                        debug!("Raw Tab - focusing next");
                         return Some(Message::KeyboardNav(keyboard_nav::Action::FocusNext));
                     }
+                    // Ctrl+G picks the GPU for Ctrl+Enter
+                    if let Key::Character(c) = &key {
+                        if c == "g" && modifiers.control() {
+                            return Some(Message::CycleGpu);
+                        }
+                    }
+                    // Other characters filter the switcher, or are the
+                    // Alt+U and Shift+1..9 shortcuts while there is no filter.
+                    // Shortcuts look at the unmodified key, the filter gets
+                    // what was typed, e.g. "!" for Shift+1
+                    if let Key::Character(c) = &key {
+                        if !modifiers.control() && !modifiers.logo() {
+                            let typed = match &modified_key {
+                                Key::Character(typed) => typed.to_string(),
+                                _ => c.to_string(),
+                            };
+                            return Some(Message::SwitcherCharacter(c.to_string(), typed, modifiers.alt(), modifiers.shift()));
+                        }
+                    }
                     // Handle number activation
                     // if let Key::Character(c) = key.clone() {
                     //     let nums = (1..=9)
@@ -968,9 +2365,28 @@ impl cosmic::Application for CosmicLauncher {
                     // Essential key handling
                     if let Key::Named(named_key) = key.clone() {
                         match named_key {
-                            Named::ArrowUp => return Some(Message::KeyboardNav(keyboard_nav::Action::FocusPrevious)),
-                            Named::ArrowDown => return Some(Message::KeyboardNav(keyboard_nav::Action::FocusNext)),
-                            Named::Escape => return Some(Message::Hide),
+                            Named::ArrowLeft if modifiers.shift() => {
+                                return Some(Message::MoveSelected(MoveTarget::PreviousWorkspace));
+                            }
+                            Named::ArrowRight if modifiers.shift() => {
+                                return Some(Message::MoveSelected(MoveTarget::NextWorkspace));
+                            }
+                            Named::ArrowUp if modifiers.shift() => {
+                                return Some(Message::MoveSelected(MoveTarget::PreviousOutput));
+                            }
+                            Named::ArrowDown if modifiers.shift() => {
+                                return Some(Message::MoveSelected(MoveTarget::NextOutput));
+                            }
+                            Named::ArrowUp => return Some(Message::Navigate(Direction::Up)),
+                            Named::ArrowDown => return Some(Message::Navigate(Direction::Down)),
+                            Named::ArrowLeft => return Some(Message::Navigate(Direction::Left)),
+                            Named::ArrowRight => return Some(Message::Navigate(Direction::Right)),
+                            Named::Escape => return Some(Message::Cancel),
+                            Named::Space => return Some(Message::SwitcherType(" ".to_string())),
+                            Named::Backspace => return Some(Message::SwitcherBackspace),
+                            Named::Delete => return Some(Message::CloseWindow),
+                            Named::Enter if modifiers.control() => return Some(Message::GpuActivate),
+                            Named::Enter if modifiers.shift() => return Some(Message::ShiftActivate),
                             Named::Enter => return Some(Message::Activate(None)),
                             _ => {}
                         }
@@ -1057,7 +2473,7 @@ impl CosmicLauncher {
                     Image::new(handle)
                         .width(Length::Fixed(70.0))
                         .height(Length::Fixed(40.0))
-                        .content_fit(cosmic::iced::ContentFit::Fill),
+                        .content_fit(cosmic::iced::ContentFit::Contain),
                     container(app_icon)
                         .width(Length::Fixed(20.0))
                         .height(Length::Fixed(20.0))
@@ -1107,7 +2523,7 @@ impl CosmicLauncher {
         };
 
         // Create clickable search result item
-        mouse_area(
+        let result = mouse_area(
             container(
                 row![
                     icon_element,
@@ -1142,22 +2558,31 @@ impl CosmicLauncher {
                             None
                         };
                         
-                        column![
-                            // App name
-                            if is_focused {
-                                text(display_name).size(14).class(cosmic::theme::Text::Accent).wrapping(Wrapping::Word)
-                            } else {
-                                text(display_name).size(14).wrapping(Wrapping::Word)
-                            },
-                            // Description (if available)
-                            if let Some(desc) = description {
-                                text(desc).size(12).class(cosmic::theme::Text::Default).wrapping(Wrapping::Word)
-                            } else {
-                                text("").size(0) // Empty placeholder
-                            }
-                        ]
-                        .spacing(2)
-                        .width(Length::Fixed(380.0)) // Adjusted width for smaller rectangular screenshot area
+                        let lines = if let Some(provided) = self.provider_items.get(&item.id) {
+                            // Provider results show the characters the query matched
+                            column![
+                                highlighted_text(&item.name, &provided.result.name_highlights, 14., is_focused),
+                                highlighted_text(&item.description, &provided.result.description_highlights, 12., false),
+                            ]
+                        } else {
+                            column![
+                                // App name
+                                if is_focused {
+                                    text(display_name).size(14).class(cosmic::theme::Text::Accent).wrapping(Wrapping::Word)
+                                } else {
+                                    text(display_name).size(14).wrapping(Wrapping::Word)
+                                },
+                                // Description (if available)
+                                if let Some(desc) = description {
+                                    text(desc).size(12).class(cosmic::theme::Text::Default).wrapping(Wrapping::Word)
+                                } else {
+                                    text("").size(0) // Empty placeholder
+                                }
+                            ]
+                        };
+                        lines
+                            .spacing(2)
+                            .width(Length::Fixed(380.0)) // Adjusted width for smaller rectangular screenshot area
                     }
                 ]
                 .spacing(12)
@@ -1165,19 +2590,65 @@ impl CosmicLauncher {
             )
             .padding(12) // Consistent padding with window items
             .width(Length::Fixed(520.0))
-            .class(if is_focused {
+            .class(if is_focused && self.expanded_focus.is_none() {
                 cosmic::theme::Container::Primary
             } else {
                 cosmic::theme::Container::Card
             })
         )
-        .on_press(Message::Activate(Some(idx)))
+        .on_press(Message::Activate(Some(idx)));
+
+        // Calculator, file, web and window results have no actions
+        if !self.may_have_actions(item) {
+            return result.into();
+        }
+        let expanded = self
+            .expanded
+            .as_ref()
+            .filter(|(id, _)| *id == item.id)
+            .map(|(_, options)| options);
+        let chevron = mouse_area(
+            container(text(if expanded.is_some() { "⌄" } else { "›" }).size(18))
+                .center_x(Length::Fixed(24.0))
+                .center_y(Length::Fixed(40.0)),
+        )
+        .on_press(Message::ExpandActions(idx));
+        let mut actions = column![].spacing(4);
+
+        // Desktop actions, launched like the application itself
+        for (option_idx, option) in expanded.into_iter().flatten().enumerate() {
+            let is_selected = is_focused && self.expanded_focus == Some(option_idx);
+            actions = actions.push(
+                mouse_area(
+                    container(if is_selected {
+                        text(&option.name).size(13).class(cosmic::theme::Text::Accent)
+                    } else {
+                        text(&option.name).size(13)
+                    })
+                    .padding([6, 12])
+                    .width(Length::Fixed(480.0))
+                    .class(if is_selected {
+                        cosmic::theme::Container::Primary
+                    } else {
+                        cosmic::theme::Container::Card
+                    }),
+                )
+                .on_press(Message::ActivateAction(item.id, option.id)),
+            );
+        }
+        column![
+            row![result, chevron].spacing(4).align_y(Alignment::Center),
+            container(actions).padding([0, 0, 0, 40])
+        ]
+        .spacing(4)
         .into()
     }
 
     fn create_window_item_element<'a>(&self, item: &'a SearchResult, idx: usize, is_selected: bool) -> Element<'a, Message> {
         // Try to find screenshot for this window
         let screenshot = self.find_screenshot_for_item(item);
+        let layout = self.card_layout();
+        let preview = layout.preview;
         
         // Create preview image or fallback icon - fixed size and centered
         let preview_element = if let Some(wayland_image) = screenshot {
@@ -1189,24 +2660,38 @@ impl CosmicLauncher {
             );
             
             // Create app icon element
-            let app_icon = match &item.icon {
-                Some(IconSource::Name(icon_name)) => {
-                    icon::from_name(icon_name.clone()).size(20)
+            let app_icon: Element<'a, Message> = match (self.item_icon(item), &item.icon) {
+                (Some(handle), _) => icon::icon(handle).size(20).into(),
+                (None, Some(IconSource::Name(icon_name))) => {
+                    icon::from_name(icon_name.clone()).size(20).into()
                 }
-                Some(IconSource::Mime(_mime_type)) => {
-                    icon::from_name("text-x-generic").size(20)
+                (None, Some(IconSource::Mime(_mime_type))) => {
+                    icon::from_name("text-x-generic").size(20).into()
                 }
                 _ => {
-                    icon::from_name("application-x-executable").size(20)
+                    icon::from_name("application-x-executable").size(20).into()
                 }
             };
             
+            // Minimized windows keep their last capture, dimmed
+            let minimized = self
+                .toplevels
+                .get(item.id as usize)
+                .is_some_and(|t| self.alt_tab_mode && t.state.contains(&ToplevelState::Minimized));
+
             container(
                 column![
-                    Image::new(handle)
-                        .width(Length::Fixed(220.0))
-                        .height(Length::Fixed(125.0))
-                        .content_fit(cosmic::iced::ContentFit::Fill),
+                    // Letterbox the preview instead of stretching it to the card
+                    container(
+                        Image::new(handle)
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .content_fit(cosmic::iced::ContentFit::Contain)
+                            .opacity(if minimized { 0.4 } else { 1.0 })
+                    )
+                    .center_x(Length::Fixed(preview.width))
+                    .center_y(Length::Fixed(preview.height))
+                    .class(cosmic::theme::Container::Background),
                     container(app_icon)
                         .width(Length::Fixed(24.0))
                         .height(Length::Fixed(24.0))
@@ -1216,20 +2701,27 @@ impl CosmicLauncher {
                 .spacing(4)
                 .align_x(Alignment::Center)
             )
-            .width(Length::Fixed(220.0))
-            .height(Length::Fixed(155.0)) // Height to accommodate app icon below
+            .width(Length::Fixed(preview.width))
+            .height(Length::Fixed(preview.height + 30.0)) // Height to accommodate app icon below
             .center_x(Length::Fill)
             .center_y(Length::Fill)
         } else {
             // Fallback: try to show app icon if available, otherwise use window emoji
-            match &item.icon {
-                Some(IconSource::Name(icon_name)) => {
+            match (self.item_icon(item), &item.icon) {
+                (Some(handle), _) => {
+                    container(icon::icon(handle).size(40))
+                    .width(Length::Fixed(preview.width))
+                    .height(Length::Fixed(preview.height))
+                    .center_x(Length::Fill)
+                    .center_y(Length::Fill)
+                }
+                (None, Some(IconSource::Name(icon_name))) => {
                     container(
                         icon::from_name(icon_name.clone())
                             .size(40)
                     )
-                    .width(Length::Fixed(220.0))
-                    .height(Length::Fixed(125.0))
+                    .width(Length::Fixed(preview.width))
+                    .height(Length::Fixed(preview.height))
                     .center_x(Length::Fill)
                     .center_y(Length::Fill)
                 }
@@ -1239,8 +2731,8 @@ impl CosmicLauncher {
                         text(if is_selected { "▶️" } else { "🪟" })
                             .size(32)
                     )
-                    .width(Length::Fixed(220.0))
-                    .height(Length::Fixed(125.0))
+                    .width(Length::Fixed(preview.width))
+                    .height(Length::Fixed(preview.height))
                     .center_x(Length::Fill)
                     .center_y(Length::Fill)
                 }
@@ -1253,11 +2745,22 @@ impl CosmicLauncher {
             preview_element,
             // Only show description text (second line) with consistent size and color for selection
             container(
-                if is_selected {
-                    text(&item.description).size(14).class(cosmic::theme::Text::Accent)
-                } else {
-                    text(&item.description).size(14)
-                }
+                column![
+                    if is_selected {
+                        text(&item.description).size(14).class(cosmic::theme::Text::Accent)
+                    } else {
+                        text(&item.description).size(14)
+                    },
+                    // Workspace badge, updated when the window is moved
+                    text(self.workspace_badge(item).unwrap_or_default()).size(12),
+                    text(self.state_badges(item).join(" · ")).size(12),
+                    if self.is_urgent(item) {
+                        text("● Needs attention").size(12).class(cosmic::theme::Text::Accent)
+                    } else {
+                        text("").size(12)
+                    }
+                ]
+                .spacing(4)
             )
             .width(Length::Fill)
             .center_y(Length::Fill)
@@ -1269,8 +2772,8 @@ impl CosmicLauncher {
         mouse_area(
             container(content)
                 .padding(12) // Consistent padding - no size changes
-                .width(Length::Fixed(600.0))
-                .height(Length::Fixed(180.0)) // Reduced to accommodate smaller screenshots
+                .width(Length::Fixed(layout.card_width))
+                .height(Length::Fixed(preview.height + 55.0))
                 .class(if is_selected {
                     cosmic::theme::Container::Primary // Use primary highlight for selection
                 } else {
@@ -1294,7 +2797,13 @@ impl CosmicLauncher {
                     text_input::search_input("Type to search", &self.input_value)
                         .on_input(Message::InputChanged)
                         .width(600) // Increased width
-                        .id(INPUT_ID.clone())
+                        .id(INPUT_ID.clone()),
+                    // Only worth mentioning with a second GPU to launch on
+                    text(match self.launch_gpu().filter(|_| self.gpus.len() > 1) {
+                        Some((_, gpu)) => format!("Ctrl+Enter launches on {}, Ctrl+G picks another GPU", gpu.name),
+                        None => String::new(),
+                    })
+                    .size(12)
                 ]
                 .spacing(8)
                 .align_x(Alignment::Center)
@@ -1303,15 +2812,17 @@ impl CosmicLauncher {
             .class(cosmic::theme::Container::Card) // Add background card styling
         );
 
-        // Show results below search - use search item elements when there's input, window elements when empty
-        if self.launcher_items.is_empty() {
+        // Run mode lists commands instead of pop-launcher results
+        if let Some(input) = run_command::parse(&self.input_value) {
+            content = content.push(self.view_run_suggestions(input));
+        } else if self.launcher_items.is_empty() {
             content = content.push(text("No windows open").size(16));
         } else {
             let mut item_elements: Vec<Element<Message>> = Vec::new();
             
             for (idx, item) in self.launcher_items.iter().enumerate() {
                 let is_selected = self.active == Some(idx);
-                println!("DEBUG: Launcher rendering item {} - '{}', selected: {}", idx, item.name, is_selected);
+                debug!("Launcher rendering item {} - '{}', selected: {}", idx, item.name, is_selected);
                 
                 // Use search item elements when searching (input not empty) to show clean app names
                 // Use window item elements when browsing (input empty) to show window titles
@@ -1345,6 +2856,41 @@ impl CosmicLauncher {
             .into()
     }
 
+    fn view_run_suggestions(&self, input: run_command::RunInput) -> Element<'_, Message> {
+        let hint = if !input.command.is_empty() && shlex::split(input.command).is_none() {
+            "Unbalanced quotes in command".to_string()
+        } else if input.terminal {
+            "Enter runs the command in a terminal, Tab completes".to_string()
+        } else {
+            "Enter runs the command, start with ! to use a terminal, Tab completes".to_string()
+        };
+        let suggestions = self.run_suggestions.iter().enumerate().fold(
+            column![text(hint).size(12)].spacing(4),
+            |suggestions, (idx, suggestion)| {
+                let is_selected = self.run_focus == Some(idx);
+                suggestions.push(
+                    container(if is_selected {
+                        text(suggestion).size(14).class(cosmic::theme::Text::Accent)
+                    } else {
+                        text(suggestion).size(14)
+                    })
+                    .padding([6, 12])
+                    .width(Length::Fill)
+                    .class(if is_selected {
+                        cosmic::theme::Container::Primary
+                    } else {
+                        cosmic::theme::Container::Card
+                    }),
+                )
+            },
+        );
+        container(suggestions)
+            .width(Length::Fixed(1200.0))
+            .padding(20)
+            .class(cosmic::theme::Container::Card)
+            .into()
+    }
+
     fn view_alt_tab(&self) -> Element<'_, Message> {
         let mut content = column![]
             .spacing(15)
@@ -1355,9 +2901,16 @@ impl CosmicLauncher {
             container(
                 column![
                     text("Alt + Tab - Task Switcher").size(24),
-                    text("Use Tab to cycle through windows, release Alt to switch")
+                    text("Use Tab to cycle through windows, release Alt to switch, type to filter, Shift+arrows or Shift+1..9 to move the window, Alt+U to jump to a window asking for attention (before typing a filter)")
                         .size(14)
-                        .class(cosmic::theme::Text::Default)
+                        .class(cosmic::theme::Text::Default),
+                    if !self.switcher_filtering() {
+                        text("").size(0)
+                    } else {
+                        text(format!("Filter: {}", self.switcher_filter))
+                            .size(16)
+                            .class(cosmic::theme::Text::Accent)
+                    }
                 ]
                 .spacing(8)
                 .align_x(Alignment::Center)
@@ -1367,7 +2920,9 @@ impl CosmicLauncher {
         );
 
         // Show window items
-        if self.launcher_items.is_empty() {
+        if self.launcher_items.is_empty() && self.switcher_filtering() {
+            content = content.push(text("No windows match").size(16));
+        } else if self.launcher_items.is_empty() {
             content = content.push(text("No windows open").size(16));
         } else {
             let mut item_elements: Vec<Element<Message>> = Vec::new();
@@ -1382,7 +2937,7 @@ impl CosmicLauncher {
             let grid = self.create_grid_layout(item_elements, 2);
             content = content.push(
                 container(grid)
-                    .width(Length::Fixed(1300.0))
+                    .width(Length::Fixed(self.card_layout().grid_width))
                     .padding(20)
                     .class(cosmic::theme::Container::Card)
             );
@@ -1394,5 +2949,210 @@ impl CosmicLauncher {
             .padding([80, 20, 20, 20])
             .into()
     }
-}
 
+    fn view_overview(&self) -> Element<'_, Message> {
+        let width = self.card_layout().grid_width - 40.;
+        let area = Size::new(width, (width * 9. / 16.).min(self.height - 200.).max(100.));
+        let mut cards = self.overview_layout(area);
+        // Draw the selected card on top of overlapping ones
+        cards.sort_by_key(|(idx, _)| self.active == Some(*idx));
+
+        let layers: Vec<Element<Message>> = cards
+            .into_iter()
+            .filter_map(|(idx, rect)| {
+                let item = self.launcher_items.get(idx)?;
+                let is_selected = self.active == Some(idx);
+                let preview: Element<Message> = match self.find_screenshot_for_item(item) {
+                    Some(wayland_image) => Image::new(Handle::from_rgba(
+                        wayland_image.width,
+                        wayland_image.height,
+                        wayland_image.img.clone(),
+                    ))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .content_fit(cosmic::iced::ContentFit::Contain)
+                    .into(),
+                    None => container(icon::icon(self.app_icon(self.toplevels.get(item.id as usize).map_or("", |t| &t.app_id))).size(32))
+                        .center_x(Length::Fill)
+                        .center_y(Length::Fill)
+                        .into(),
+                };
+                let title = if is_selected {
+                    text(&item.name).size(12).class(cosmic::theme::Text::Accent)
+                } else {
+                    text(&item.name).size(12)
+                };
+                let card = iced::widget::mouse_area(
+                    container(column![preview, title].spacing(4).align_x(Alignment::Center))
+                        .padding(4)
+                        .width(Length::Fixed(rect.width.max(40.)))
+                        .height(Length::Fixed(rect.height.max(40.)))
+                        .class(if is_selected {
+                            cosmic::theme::Container::Primary
+                        } else {
+                            cosmic::theme::Container::Card
+                        }),
+                )
+                .on_enter(Message::Select(idx))
+                .on_press(Message::Activate(Some(idx)));
+
+                // Offset each card inside a full size layer of the stack
+                Some(
+                    container(card)
+                        .padding(Padding {
+                            top: rect.y,
+                            left: rect.x,
+                            right: 0.,
+                            bottom: 0.,
+                        })
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .into(),
+                )
+            })
+            .collect();
+
+        let content = column![
+            container(
+                column![
+                    text("Overview").size(24),
+                    text("Use the arrow keys or the mouse to pick a window, Enter to switch")
+                        .size(14)
+                        .class(cosmic::theme::Text::Default)
+                ]
+                .spacing(8)
+                .align_x(Alignment::Center)
+            )
+            .padding(20)
+            .class(cosmic::theme::Container::Card),
+            container(
+                stack(layers)
+                    .width(Length::Fixed(area.width))
+                    .height(Length::Fixed(area.height))
+            )
+            .padding(20)
+            .class(cosmic::theme::Container::Card)
+        ]
+        .spacing(15)
+        .align_x(Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .center_x(Length::Fill)
+            .padding([80, 20, 20, 20])
+            .into()
+    }
+
+    fn view_workspaces(&self) -> Element<'_, Message> {
+        let layout = self.card_layout();
+        let columns = self.workspaces.len().clamp(1, 3);
+        let card_width = (layout.grid_width - 40. - 8. * (columns - 1) as f32) / columns as f32;
+        let preview = Size::new(card_width - 24., ((card_width - 24.) * 9. / 16.).round());
+
+        let cards: Vec<Element<Message>> = self
+            .workspaces
+            .iter()
+            .enumerate()
+            .map(|(idx, workspace)| {
+                let is_selected = self.active == Some(idx);
+                let preview_element: Element<Message> =
+                    match self.workspace_thumbnails.get(&workspace.handle) {
+                        Some(wayland_image) => Image::new(Handle::from_rgba(
+                            wayland_image.width,
+                            wayland_image.height,
+                            wayland_image.img.clone(),
+                        ))
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .content_fit(cosmic::iced::ContentFit::Contain)
+                        .into(),
+                        // Never shown yet since the launcher started
+                        None => column![
+                            icon::from_name("video-display-symbolic").size(32),
+                            text("No preview yet").size(12),
+                        ]
+                        .spacing(4)
+                        .align_x(Alignment::Center)
+                        .into(),
+                    };
+                let icons = self
+                    .toplevels
+                    .iter()
+                    .filter(|t| t.workspaces.contains(&workspace.handle))
+                    .take(8)
+                    .fold(row![].spacing(4), |icons, toplevel| {
+                        icons.push(icon::icon(self.app_icon(&toplevel.app_id)).size(24))
+                    });
+                let label = if workspace.urgent {
+                    format!("{} ●", workspace.name)
+                } else {
+                    workspace.name.clone()
+                };
+                let name = if is_selected || workspace.urgent {
+                    text(label).size(14).class(cosmic::theme::Text::Accent)
+                } else {
+                    text(label).size(14)
+                };
+
+                iced::widget::mouse_area(
+                    container(
+                        column![
+                            container(preview_element)
+                                .center_x(Length::Fixed(preview.width))
+                                .center_y(Length::Fixed(preview.height))
+                                .class(cosmic::theme::Container::Background),
+                            name,
+                            icons,
+                        ]
+                        .spacing(6)
+                        .align_x(Alignment::Center),
+                    )
+                    .padding(12)
+                    .width(Length::Fixed(card_width))
+                    .class(if is_selected {
+                        cosmic::theme::Container::Primary
+                    } else {
+                        cosmic::theme::Container::Card
+                    }),
+                )
+                .on_enter(Message::Select(idx))
+                .on_press(Message::Activate(Some(idx)))
+                .into()
+            })
+            .collect();
+
+        let mut content = column![
+            container(
+                column![
+                    text("Workspaces").size(24),
+                    text("Use the arrow keys to pick a workspace, Enter to switch, Shift+Enter to move the focused window there")
+                        .size(14)
+                        .class(cosmic::theme::Text::Default)
+                ]
+                .spacing(8)
+                .align_x(Alignment::Center)
+            )
+            .padding(20)
+            .class(cosmic::theme::Container::Card)
+        ]
+        .spacing(15)
+        .align_x(Alignment::Center);
+
+        if cards.is_empty() {
+            content = content.push(text("No workspaces available").size(16));
+        } else {
+            content = content.push(
+                container(self.create_grid_layout(cards, columns))
+                    .width(Length::Fixed(layout.grid_width))
+                    .padding(20)
+                    .class(cosmic::theme::Container::Card),
+            );
+        }
+
+        container(content)
+            .width(Length::Fill)
+            .center_x(Length::Fill)
+            .padding([80, 20, 20, 20])
+            .into()
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Detection of the wayland protocols the compositor supports.
+//!
+//! Features backed by a missing protocol are turned off instead of failing,
+//! e.g. switcher cards fall back to app icons without screencopy.
+
+use crate::wayland_subscription::WaylandError;
+use cosmic::cctk::wayland_client::{
+    globals::{registry_queue_init, GlobalList, GlobalListContents},
+    protocol::wl_registry,
+    Connection, Dispatch, QueueHandle,
+};
+use std::fmt::Write;
+
+/// Protocols the launcher knows how to use, with what they are needed for.
+const PROTOCOLS: &[(&str, &str)] = &[
+    ("wl_shm", "shared memory buffers for thumbnails"),
+    ("wl_seat", "window activation"),
+    ("ext_foreign_toplevel_list_v1", "toplevel tracking"),
+    (
+        "zwlr_foreign_toplevel_manager_v1",
+        "toplevel tracking and activation on wlroots",
+    ),
+    ("zcosmic_toplevel_info_v1", "window state, outputs and workspaces"),
+    ("zcosmic_toplevel_manager_v1", "window activation"),
+    ("ext_workspace_manager_v1", "workspace overview"),
+    ("ext_image_copy_capture_manager_v1", "window thumbnails"),
+    (
+        "ext_foreign_toplevel_image_capture_source_manager_v1",
+        "window thumbnails",
+    ),
+    (
+        "ext_output_image_capture_source_manager_v1",
+        "workspace previews",
+    ),
+];
+
+/// Protocol used to list and manage toplevels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToplevelBackend {
+    /// ext-foreign-toplevel-list, extended by the cosmic toplevel protocols
+    /// when the compositor has them.
+    Cosmic,
+    /// wlr-foreign-toplevel-management, for wlroots based compositors.
+    Wlr,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Capabilities {
+    /// Bound version of every known protocol, `None` when it is not advertised.
+    globals: Vec<(&'static str, Option<u32>)>,
+}
+
+impl Capabilities {
+    pub fn from_globals(globals: &GlobalList) -> Self {
+        let globals = globals.contents().with_list(|list| {
+            PROTOCOLS
+                .iter()
+                .map(|(interface, _)| {
+                    let version = list
+                        .iter()
+                        .filter(|global| global.interface == *interface)
+                        .map(|global| global.version)
+                        .max();
+                    (*interface, version)
+                })
+                .collect()
+        });
+        Self { globals }
+    }
+
+    /// Connects to the compositor just to find out what it supports.
+    pub fn probe() -> Result<Self, WaylandError> {
+        let conn =
+            Connection::connect_to_env().map_err(|err| WaylandError::Connect(err.to_string()))?;
+        let (globals, _event_queue) = registry_queue_init::<ProbeData>(&conn)
+            .map_err(|err| WaylandError::Registry(err.to_string()))?;
+        Ok(Self::from_globals(&globals))
+    }
+
+    pub fn has(&self, interface: &str) -> bool {
+        self.globals
+            .iter()
+            .any(|(name, version)| *name == interface && version.is_some())
+    }
+
+    /// Picks the cosmic protocols when available, then wlr, then a bare
+    /// ext-foreign-toplevel-list.
+    pub fn toplevel_backend(&self) -> Option<ToplevelBackend> {
+        let ext_list = self.has("ext_foreign_toplevel_list_v1");
+        if ext_list && self.has("zcosmic_toplevel_info_v1") {
+            Some(ToplevelBackend::Cosmic)
+        } else if self.has("zwlr_foreign_toplevel_manager_v1") {
+            Some(ToplevelBackend::Wlr)
+        } else if ext_list {
+            Some(ToplevelBackend::Cosmic)
+        } else {
+            None
+        }
+    }
+
+    pub fn toplevel_tracking(&self) -> bool {
+        self.toplevel_backend().is_some()
+    }
+
+    pub fn toplevel_management(&self) -> bool {
+        match self.toplevel_backend() {
+            // Activation goes through the cosmic handle of a toplevel
+            Some(ToplevelBackend::Cosmic) => {
+                self.has("zcosmic_toplevel_info_v1") && self.has("zcosmic_toplevel_manager_v1")
+            }
+            Some(ToplevelBackend::Wlr) => true,
+            None => false,
+        }
+    }
+
+    pub fn workspaces(&self) -> bool {
+        self.has("ext_workspace_manager_v1")
+    }
+
+    pub fn thumbnails(&self) -> bool {
+        // Capture sources are created from ext-foreign-toplevel-list handles
+        self.toplevel_backend() == Some(ToplevelBackend::Cosmic)
+            && self.has("wl_shm")
+            && self.has("ext_image_copy_capture_manager_v1")
+            && self.has("ext_foreign_toplevel_image_capture_source_manager_v1")
+    }
+
+    /// Human readable summary used by `cosmic-launcher diagnose`.
+    pub fn report(&self) -> String {
+        let mut report = String::from("Wayland protocols:\n");
+        for ((interface, version), (_, purpose)) in self.globals.iter().zip(PROTOCOLS) {
+            let status = match version {
+                Some(version) => format!("v{version}"),
+                None => String::from("missing"),
+            };
+            let _ = writeln!(report, "  {interface:<56} {status:<8} {purpose}");
+        }
+
+        let backend = match self.toplevel_backend() {
+            Some(ToplevelBackend::Cosmic) => "cosmic",
+            Some(ToplevelBackend::Wlr) => "wlr",
+            None => "none",
+        };
+        let _ = writeln!(report, "\nToplevel backend: {backend}");
+
+        report.push_str("\nFeatures:\n");
+        let features = [
+            ("Toplevel tracking", self.toplevel_tracking()),
+            ("Window activation", self.toplevel_management()),
+            ("Window thumbnails", self.thumbnails()),
+            ("Workspace overview", self.workspaces()),
+        ];
+        for (feature, enabled) in features {
+            let status = if enabled { "enabled" } else { "disabled" };
+            let _ = writeln!(report, "  {feature:<56} {status}");
+        }
+        if !self.thumbnails() {
+            report.push_str("\nSwitcher cards will show app icons instead of thumbnails.\n");
+        }
+        report
+    }
+}
+
+/// Dispatch target for the probe connection, which never handles events.
+struct ProbeData;
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for ProbeData {
+    fn event(
+        _state: &mut Self,
+        _proxy: &wl_registry::WlRegistry,
+        _event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
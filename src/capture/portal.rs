@@ -0,0 +1,69 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Output capture through the XDG desktop portal screenshot interface.
+//!
+//! The portal has no notion of windows or single outputs, it captures the
+//! whole desktop. With several outputs every workspace card shows all of
+//! them. Portals may ask the user for permission on the first capture.
+
+use super::{CaptureBackend, CaptureCapabilities, CaptureSource, Session};
+use crate::wayland_subscription::CaptureError;
+use ashpd::desktop::screenshot::Screenshot;
+use std::sync::Arc;
+
+pub struct PortalCapture;
+
+impl CaptureBackend for PortalCapture {
+    fn name(&self) -> &'static str {
+        "xdg-desktop-portal"
+    }
+
+    fn capabilities(&self) -> CaptureCapabilities {
+        CaptureCapabilities {
+            toplevels: false,
+            outputs: true,
+        }
+    }
+
+    fn capture(
+        &self,
+        source: &CaptureSource,
+        session: Arc<Session>,
+    ) -> Result<image::RgbaImage, CaptureError> {
+        if !matches!(source, CaptureSource::Output { .. }) {
+            return Err(CaptureError::UnsupportedSource);
+        }
+
+        // Capture threads have no runtime, the portal call needs one
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|err| CaptureError::Backend(err.to_string()))?;
+        let screenshot = runtime
+            .block_on(async {
+                Screenshot::request()
+                    .interactive(false)
+                    .modal(false)
+                    .send()
+                    .await?
+                    .response()
+            })
+            .map_err(|err| CaptureError::Backend(err.to_string()))?;
+
+        let path = screenshot
+            .uri()
+            .to_file_path()
+            .map_err(|()| CaptureError::Backend(format!("not a file: {}", screenshot.uri())))?;
+        let img = image::open(&path).map_err(|err| CaptureError::Image(err.to_string()));
+        // The portal saves a file for every capture, it is only needed here
+        if let Err(err) = std::fs::remove_file(&path) {
+            tracing::warn!("Failed to remove portal screenshot {}: {err}", path.display());
+        }
+
+        if session.is_cancelled() {
+            return Err(CaptureError::Cancelled);
+        }
+        Ok(img?.to_rgba8())
+    }
+}
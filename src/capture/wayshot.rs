@@ -0,0 +1,63 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Output capture through libwayshot and wlr-screencopy, for wlroots
+//! compositors without ext-image-copy-capture. libwayshot cannot capture
+//! single windows.
+
+use super::{CaptureBackend, CaptureCapabilities, CaptureSource, Session};
+use crate::wayland_subscription::CaptureError;
+use libwayshot::WayshotConnection;
+use std::sync::Arc;
+
+/// Connects for every capture, workspace previews are captured rarely and a
+/// fresh connection always knows the current outputs.
+pub struct WayshotCapture;
+
+impl CaptureBackend for WayshotCapture {
+    fn name(&self) -> &'static str {
+        "wayshot"
+    }
+
+    fn capabilities(&self) -> CaptureCapabilities {
+        CaptureCapabilities {
+            toplevels: false,
+            outputs: true,
+        }
+    }
+
+    fn supports(&self, source: &CaptureSource) -> bool {
+        matches!(source, CaptureSource::Output { name: Some(_), .. })
+    }
+
+    fn capture(
+        &self,
+        source: &CaptureSource,
+        session: Arc<Session>,
+    ) -> Result<image::RgbaImage, CaptureError> {
+        let CaptureSource::Output {
+            name: Some(name), ..
+        } = source
+        else {
+            return Err(CaptureError::UnsupportedSource);
+        };
+
+        // libwayshot has its own connection, outputs are matched by name
+        let connection =
+            WayshotConnection::new().map_err(|err| CaptureError::Backend(err.to_string()))?;
+        let output = connection
+            .get_all_outputs()
+            .iter()
+            .find(|output| output.name == *name)
+            .ok_or(CaptureError::NotFound)?;
+        let img = connection
+            .screenshot_single_output(output, false)
+            .map_err(|err| CaptureError::Backend(err.to_string()))?
+            .to_rgba8();
+
+        if session.is_cancelled() {
+            return Err(CaptureError::Cancelled);
+        }
+        Ok(img)
+    }
+}
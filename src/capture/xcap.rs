@@ -0,0 +1,84 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Capture through xcap.
+//!
+//! xcap has its own connection to the display server and does not know the
+//! handler's protocol objects, so windows are looked up by title and app
+//! name and outputs by name. On Wayland it only sees XWayland windows.
+
+use super::{CaptureBackend, CaptureCapabilities, CaptureSource, Session};
+use crate::wayland_subscription::CaptureError;
+use std::sync::Arc;
+
+pub struct XcapCapture;
+
+impl CaptureBackend for XcapCapture {
+    fn name(&self) -> &'static str {
+        "xcap"
+    }
+
+    fn capabilities(&self) -> CaptureCapabilities {
+        CaptureCapabilities {
+            toplevels: true,
+            outputs: true,
+        }
+    }
+
+    fn supports(&self, source: &CaptureSource) -> bool {
+        match source {
+            CaptureSource::Toplevel { title, .. } => !title.is_empty(),
+            CaptureSource::Output { name, .. } => name.is_some(),
+        }
+    }
+
+    fn capture(
+        &self,
+        source: &CaptureSource,
+        session: Arc<Session>,
+    ) -> Result<image::RgbaImage, CaptureError> {
+        let img = match source {
+            CaptureSource::Toplevel { title, app_id, .. } => {
+                find_window(title, app_id)?.capture_image()
+            }
+            CaptureSource::Output {
+                name: Some(name), ..
+            } => find_monitor(name)?.capture_image(),
+            CaptureSource::Output { name: None, .. } => {
+                return Err(CaptureError::UnsupportedSource);
+            }
+        }
+        .map_err(|err| CaptureError::Backend(err.to_string()))?;
+
+        // xcap captures synchronously, a cancelled capture only drops its result
+        if session.is_cancelled() {
+            return Err(CaptureError::Cancelled);
+        }
+        Ok(img)
+    }
+}
+
+/// Visible window titled `title`, preferring one whose app name is `app_id`
+/// when several have the same title.
+fn find_window(title: &str, app_id: &str) -> Result<::xcap::Window, CaptureError> {
+    ::xcap::Window::all()
+        .map_err(|err| CaptureError::Backend(err.to_string()))?
+        .into_iter()
+        .filter(|window| {
+            window.title().is_ok_and(|t| t == title) && !window.is_minimized().unwrap_or(false)
+        })
+        .max_by_key(|window| {
+            window
+                .app_name()
+                .is_ok_and(|name| name.eq_ignore_ascii_case(app_id))
+        })
+        .ok_or(CaptureError::NotFound)
+}
+
+fn find_monitor(name: &str) -> Result<::xcap::Monitor, CaptureError> {
+    ::xcap::Monitor::all()
+        .map_err(|err| CaptureError::Backend(err.to_string()))?
+        .into_iter()
+        .find(|monitor| monitor.name().is_ok_and(|n| n == name))
+        .ok_or(CaptureError::NotFound)
+}
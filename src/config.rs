@@ -1,3 +1,7 @@
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn profile() -> &'static str {
@@ -6,3 +10,250 @@ pub fn profile() -> &'static str {
         .nth_back(3)
         .unwrap_or("unknown")
 }
+
+/// What activating an application does when it already has open windows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LaunchPolicy {
+    /// Always start a new instance.
+    #[default]
+    AlwaysLaunch,
+    /// Focus the newest window of the application.
+    FocusExisting,
+    /// Focus the window if the application has exactly one, launch otherwise.
+    FocusIfSingle,
+}
+
+/// Settings read from cosmic-config, under `com.system76.CosmicLauncher`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, CosmicConfigEntry)]
+#[version = 1]
+pub struct LauncherConfig {
+    pub launch_policy: LaunchPolicy,
+    /// Terminal command for `Terminal=true` entries, the program to run is
+    /// appended, e.g. `["foot", "-e"]`. Empty uses the COSMIC default.
+    pub terminal: Vec<String>,
+    /// Extra environment and arguments by app id.
+    pub apps: HashMap<String, AppOverride>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppOverride {
+    pub env: HashMap<String, String>,
+    /// Inserted before the first field code of `Exec`, or appended.
+    pub args: Vec<String>,
+}
+
+/// Command line and environment for a launch, after applying the config.
+#[derive(Clone, Debug)]
+pub struct LaunchCommand {
+    pub exec: String,
+    pub terminal: bool,
+    pub env: Vec<(String, String)>,
+}
+
+impl LaunchCommand {
+    /// Environment to launch with: the activation `token`, the variables
+    /// selecting a GPU, then the configured ones, later ones replacing
+    /// earlier ones with the same name.
+    pub fn envs(
+        &self,
+        token: Option<String>,
+        gpu_envs: Option<HashMap<String, String>>,
+    ) -> Vec<(String, String)> {
+        let mut envs: Vec<(String, String)> = Vec::new();
+        let token = token.into_iter().flat_map(|token| {
+            [
+                ("XDG_ACTIVATION_TOKEN".to_string(), token.clone()),
+                ("DESKTOP_STARTUP_ID".to_string(), token),
+            ]
+        });
+        // Sorted so the result does not depend on map order
+        let mut gpu_envs: Vec<_> = gpu_envs.into_iter().flatten().collect();
+        gpu_envs.sort();
+        for (key, value) in token.chain(gpu_envs).chain(self.env.iter().cloned()) {
+            envs.retain(|(k, _)| *k != key);
+            envs.push((key, value));
+        }
+        envs
+    }
+}
+
+impl LauncherConfig {
+    /// Applies the overrides for `app_id` and the configured terminal to `exec`.
+    pub fn command(&self, app_id: &str, exec: &str, terminal: bool) -> LaunchCommand {
+        // An exact key wins, other matching keys are tried in sorted order
+        // since map iteration order changes between runs
+        let app = self.apps.get(app_id).or_else(|| {
+            self.apps
+                .iter()
+                .filter(|(id, _)| crate::desktop_index::id_matches(id, None, app_id))
+                .min_by(|(a, _), (b, _)| a.cmp(b))
+                .map(|(_, app)| app)
+        });
+        let args_override = app.map(|app| &app.args).filter(|args| !args.is_empty());
+        let wrap = terminal && !self.terminal.is_empty();
+        let env = app
+            .into_iter()
+            .flat_map(|app| app.env.iter())
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        // Only re-quoted when something is inserted, so the desktop entry's
+        // own quoting and escapes are kept otherwise
+        let joined = match (args_override, wrap) {
+            (None, false) => None,
+            (args_override, wrap) => shlex::split(exec).and_then(|mut args| {
+                if let Some(app_args) = args_override {
+                    let at = args
+                        .iter()
+                        .position(|arg| arg.starts_with('%'))
+                        .unwrap_or(args.len());
+                    args.splice(at..at, app_args.iter().cloned());
+                }
+                if wrap {
+                    args.splice(0..0, self.terminal.iter().cloned());
+                }
+                join_exec(&args)
+            }),
+        };
+
+        LaunchCommand {
+            // An unparsable `Exec` is left to `launch` to report
+            exec: joined.unwrap_or_else(|| exec.to_string()),
+            terminal: terminal && !wrap,
+            env,
+        }
+    }
+
+    /// Loads the config of `app_id`, using defaults for missing or invalid keys.
+    pub fn load(app_id: &str) -> Self {
+        let config = match cosmic_config::Config::new(app_id, Self::VERSION) {
+            Ok(config) => config,
+            Err(err) => {
+                tracing::warn!("Failed to open config: {err}");
+                return Self::default();
+            }
+        };
+        Self::get_entry(&config).unwrap_or_else(|(errors, config)| {
+            for err in errors.into_iter().filter(cosmic_config::Error::is_err) {
+                tracing::warn!("Failed to load config: {err}");
+            }
+            config
+        })
+    }
+}
+
+/// Quotes `args` back into an `Exec` line, leaving field codes like `%u`
+/// unquoted so they are still expanded.
+fn join_exec(args: &[String]) -> Option<String> {
+    let quoted = args
+        .iter()
+        .map(|arg| {
+            if arg.len() == 2 && arg.starts_with('%') {
+                Ok(arg.into())
+            } else {
+                shlex::try_quote(arg)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    Some(quoted.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(args: &[&str], env: &[(&str, &str)]) -> AppOverride {
+        AppOverride {
+            env: env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    fn config<const N: usize>(apps: [(&str, AppOverride); N]) -> LauncherConfig {
+        LauncherConfig {
+            apps: apps.into_iter().map(|(id, app)| (id.to_string(), app)).collect(),
+            ..LauncherConfig::default()
+        }
+    }
+
+    #[test]
+    fn args_go_before_first_field_code() {
+        let config = config([("firefox", app(&["--private-window"], &[]))]);
+        let command = config.command("firefox", "firefox %u --new", false);
+        assert_eq!(command.exec, "firefox --private-window %u --new");
+        let command = config.command("firefox", "/usr/bin/firefox", false);
+        assert_eq!(command.exec, "/usr/bin/firefox --private-window");
+        let command = config.command("firefox", "firefox --name 'My Profile' %U", false);
+        assert_eq!(command.exec, "firefox --name 'My Profile' --private-window %U");
+    }
+
+    #[test]
+    fn exec_is_kept_without_overrides() {
+        let exec = r#""/opt/My App/run" --title="a \"b\"" %U"#;
+        let config = config([("run", app(&[], &[("LANG", "C")]))]);
+        assert_eq!(config.command("run", exec, false).exec, exec);
+        assert_eq!(config.command("other", exec, true).exec, exec);
+    }
+
+    #[test]
+    fn terminal_apps_are_wrapped() {
+        let mut config = config([]);
+        let command = config.command("htop", "htop", true);
+        assert_eq!(command.exec, "htop");
+        assert!(command.terminal, "left to the default terminal");
+
+        config.terminal = vec!["foot".into(), "-e".into()];
+        let command = config.command("htop", "htop --tree", true);
+        assert_eq!(command.exec, "foot -e htop --tree");
+        assert!(!command.terminal);
+        let command = config.command("htop", "htop", false);
+        assert_eq!(command.exec, "htop");
+    }
+
+    #[test]
+    fn env_comes_from_the_matching_override() {
+        let config = config([
+            ("org.gnome.Nautilus", app(&[], &[("GTK_DEBUG", "interactive")])),
+            ("nautilus", app(&[], &[("GDK_BACKEND", "x11")])),
+        ]);
+        // The exact key wins over the suffix match
+        let command = config.command("nautilus", "nautilus --new-window", false);
+        assert_eq!(command.env, vec![("GDK_BACKEND".into(), "x11".into())]);
+        let command = config.command("org.gnome.Nautilus", "nautilus", false);
+        assert_eq!(command.env, vec![("GTK_DEBUG".into(), "interactive".into())]);
+        assert!(config.command("org.kde.dolphin", "dolphin", false).env.is_empty());
+    }
+
+    #[test]
+    fn fallback_match_is_deterministic() {
+        for _ in 0..8 {
+            let config = config([
+                ("org.b.Files", app(&["--b"], &[])),
+                ("org.a.Files", app(&["--a"], &[])),
+            ]);
+            assert_eq!(config.command("files", "files", false).exec, "files --a");
+        }
+    }
+
+    #[test]
+    fn configured_env_wins() {
+        let config = config([("blender", app(&[], &[("DRI_PRIME", "0"), ("LANG", "C")]))]);
+        let command = config.command("blender", "blender", false);
+        let gpu_envs = HashMap::from([("DRI_PRIME".to_string(), "1".to_string())]);
+        let mut envs = command.envs(Some("token".into()), Some(gpu_envs));
+        // Configured variables come last, in map order
+        envs[2..].sort();
+        assert_eq!(
+            envs,
+            [
+                ("XDG_ACTIVATION_TOKEN", "token"),
+                ("DESKTOP_STARTUP_ID", "token"),
+                ("DRI_PRIME", "0"),
+                ("LANG", "C"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+        assert!(command.envs(None, None).iter().all(|(k, _)| k != "XDG_ACTIVATION_TOKEN"));
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Lookup of desktop entries by toplevel app id.
+//!
+//! Toplevels report an app id which usually, but not always, matches the id
+//! of their desktop entry. The index also matches `StartupWMClass`, and a
+//! bare app id like `nautilus` matches the last component of a reverse-DNS
+//! id like `org.gnome.Nautilus`. Dotted app ids only match exactly, so
+//! `org.kde.foo` never finds `org.gnome.foo`.
+
+use cosmic::desktop::{self, DesktopEntryData};
+use cosmic::widget::icon;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+/// Localized name and themed icon of an application.
+#[derive(Clone, Debug)]
+pub struct AppEntry {
+    pub name: String,
+    pub icon: icon::Handle,
+}
+
+#[derive(Debug, Default)]
+pub struct DesktopIndex {
+    /// Desktop ids and `StartupWMClass` values, lowercased.
+    by_id: HashMap<String, Arc<AppEntry>>,
+    /// Last component of reverse-DNS desktop ids, lowercased.
+    by_suffix: HashMap<String, Arc<AppEntry>>,
+    /// Localized names of all entries.
+    names: HashSet<String>,
+    /// Modification times of the application directories, their
+    /// subdirectories and entries when loaded.
+    snapshot: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl DesktopIndex {
+    /// Loads every desktop entry in the XDG application directories. Reads
+    /// all `.desktop` files, so this should not run on the UI thread.
+    pub fn load() -> Self {
+        let locales: Vec<String> = i18n_embed::DesktopLanguageRequester::requested_languages()
+            .iter()
+            .map(|lang| lang.to_string().replace('-', "_"))
+            .collect();
+        let theme = cosmic::icon_theme::default();
+
+        let mut index = Self {
+            snapshot: snapshot(&application_dirs()),
+            ..Self::default()
+        };
+        for entry in desktop::load_applications(&locales, true, None) {
+            index.insert(&entry, &theme);
+        }
+        tracing::debug!("Indexed {} desktop entry ids", index.by_id.len());
+        index
+    }
+
+    fn insert(&mut self, entry: &DesktopEntryData, theme: &str) {
+        let app = Arc::new(AppEntry {
+            name: entry.name.clone(),
+            icon: themed_icon(&entry.icon, theme),
+        });
+        let id = entry.id.to_lowercase();
+        self.names.insert(entry.name.clone());
+
+        // Earlier data dirs take precedence, like they do for desktop entries
+        if let Some(suffix) = id.rsplit('.').next().filter(|suffix| *suffix != id) {
+            self.by_suffix
+                .entry(suffix.to_string())
+                .or_insert_with(|| app.clone());
+        }
+        if let Some(wm_class) = &entry.wm_class {
+            self.by_id
+                .entry(wm_class.to_lowercase())
+                .or_insert_with(|| app.clone());
+        }
+        self.by_id.entry(id).or_insert(app);
+    }
+
+    /// Entry for a toplevel's app id, trying exact ids first.
+    pub fn lookup(&self, app_id: &str) -> Option<&AppEntry> {
+        if app_id.is_empty() {
+            return None;
+        }
+        let app_id = app_id.trim_end_matches(".desktop").to_lowercase();
+        // Flatpak and other reverse-DNS ids match exactly, plain names like
+        // `nautilus` match the end of `org.gnome.Nautilus`. Suffixes have no
+        // dots, so dotted app ids never match one.
+        self.by_id
+            .get(&app_id)
+            .or_else(|| self.by_suffix.get(&app_id))
+            .map(|app| &**app)
+    }
+
+    /// Whether an application is called `name`. pop-launcher does not say
+    /// which plugin a result comes from, this tells desktop entries apart
+    /// from calculator, file and web results.
+    pub fn has_name(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// A fresh index when an entry was added, removed or edited in the
+    /// application directories, including nested ones like
+    /// `applications/kde4`, since this one was loaded. `None` when it is
+    /// current. Walks the directories, so this should not run on the UI
+    /// thread either.
+    pub fn reloaded(&self) -> Option<Self> {
+        (snapshot(&application_dirs()) != self.snapshot).then(Self::load)
+    }
+}
+
+/// Whether a toplevel with `app_id` belongs to `entry`, using the same rules
+/// as [`DesktopIndex::lookup`].
+pub fn entry_matches(entry: &DesktopEntryData, app_id: &str) -> bool {
+    id_matches(&entry.id, entry.wm_class.as_deref(), app_id)
+}
+
+/// Whether a toplevel with `app_id` belongs to the desktop entry `id` with
+/// the optional `StartupWMClass` `wm_class`.
+pub fn id_matches(id: &str, wm_class: Option<&str>, app_id: &str) -> bool {
+    if app_id.is_empty() {
+        return false;
+    }
+    let app_id = app_id.trim_end_matches(".desktop").to_lowercase();
+    let id = id.to_lowercase();
+    // Only a bare app id may match the end of a reverse-DNS id
+    let suffix_matches = !app_id.contains('.')
+        && id
+            .rsplit_once('.')
+            .is_some_and(|(_, suffix)| suffix == app_id);
+    id == app_id
+        || wm_class.is_some_and(|wm_class| wm_class.to_lowercase() == app_id)
+        || suffix_matches
+}
+
+/// `applications` directories of the XDG data dirs, most important first.
+fn application_dirs() -> Vec<PathBuf> {
+    let Ok(dirs) = xdg::BaseDirectories::new() else {
+        return Vec::new();
+    };
+    std::iter::once(dirs.get_data_home())
+        .chain(dirs.get_data_dirs())
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// Modification times of `dirs`, their subdirectories and the `.desktop`
+/// files in them, in a stable order. Missing directories are kept with no
+/// time so they are noticed when created.
+fn snapshot(dirs: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    fn walk(dir: &Path, snapshot: &mut Vec<(PathBuf, Option<SystemTime>)>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let path = entry.path();
+            // Symlinked directories are not followed, they could loop
+            if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                snapshot.push((path.clone(), modified(&path)));
+                walk(&path, snapshot);
+            } else if path.extension().is_some_and(|ext| ext == "desktop") {
+                let modified = modified(&path);
+                snapshot.push((path, modified));
+            }
+        }
+    }
+
+    let mut snapshot = Vec::new();
+    for dir in dirs {
+        snapshot.push((dir.clone(), modified(dir)));
+        walk(dir, &mut snapshot);
+    }
+    snapshot
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Resolves an icon name in the current icon theme, falling back to a
+/// lookup by name when the theme does not have it.
+fn themed_icon(source: &desktop::IconSource, theme: &str) -> icon::Handle {
+    match source {
+        desktop::IconSource::Path(path) => icon::from_path(path.clone()),
+        desktop::IconSource::Name(name) => freedesktop_icons::lookup(name)
+            .with_theme(theme)
+            .with_size(64)
+            .with_cache()
+            .find()
+            .map(icon::from_path)
+            .unwrap_or_else(|| icon::from_name(name.as_str()).handle()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, time::Duration};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("cosmic-launcher-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("kde4")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn id_matches_exact_and_wm_class() {
+        assert!(id_matches("org.gnome.Nautilus", None, "org.gnome.Nautilus"));
+        assert!(id_matches(
+            "org.gnome.Nautilus",
+            None,
+            "org.gnome.nautilus.desktop"
+        ));
+        assert!(id_matches(
+            "google-chrome",
+            Some("Google-chrome"),
+            "google-chrome"
+        ));
+        assert!(id_matches("code", Some("Code"), "code"));
+        assert!(!id_matches("firefox", None, ""));
+    }
+
+    #[test]
+    fn id_matches_suffix_only_for_bare_app_ids() {
+        assert!(id_matches("org.gnome.Nautilus", None, "nautilus"));
+        // Two dotted ids never match by their last component
+        assert!(!id_matches("org.gnome.Terminal", None, "org.kde.terminal"));
+        assert!(!id_matches("terminal", None, "org.kde.terminal"));
+        assert!(!id_matches("org.gnome.Nautilus", None, "gnome.nautilus"));
+    }
+
+    #[test]
+    fn snapshot_notices_nested_entries() {
+        let dir = temp_dir("nested");
+        let dirs = [dir.clone()];
+        let before = snapshot(&dirs);
+
+        let entry = dir.join("kde4/kate.desktop");
+        fs::write(&entry, "[Desktop Entry]\n").unwrap();
+        let added = snapshot(&dirs);
+        assert_ne!(added, before);
+        assert!(added.iter().any(|(path, _)| *path == entry));
+
+        // Edited in place, which leaves the directory times alone
+        let file = fs::File::options().write(true).open(&entry).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_ne!(snapshot(&dirs), added);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn snapshot_ignores_other_files() {
+        let dir = temp_dir("other");
+        let dirs = [dir.clone(), dir.join("missing")];
+        let before = snapshot(&dirs);
+        assert_eq!(before.last(), Some(&(dir.join("missing"), None)));
+
+        let file = fs::File::create(dir.join("mimeinfo.cache")).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        fs::File::open(&dir)
+            .unwrap()
+            .set_modified(before[0].1.unwrap())
+            .unwrap();
+        assert_eq!(snapshot(&dirs), before);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
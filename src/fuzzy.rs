@@ -0,0 +1,183 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Fuzzy matching of search input against window titles and app names.
+//!
+//! The query matches when its characters appear in order in the text,
+//! ignoring case. Matches are scored by how many characters are consecutive
+//! or start a word, so `ffx` ranks "Firefox" above "Buffer Flux".
+
+const MATCH: f32 = 1.;
+const CONSECUTIVE: f32 = 2.;
+const WORD_START: f32 = 3.;
+const TEXT_START: f32 = 2.;
+const GAP: f32 = 0.1;
+const MAX_GAP_PENALTY: f32 = 1.;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Match {
+    /// Between 0 and 1, 1 when the query is a prefix of the text.
+    pub score: f32,
+    /// Char indexes of the matched characters in the text.
+    pub positions: Vec<usize>,
+}
+
+/// Matches `query` against `text`, `None` when not all of its characters
+/// appear in order. An empty query matches everything with score 1.
+pub fn matches(query: &str, text: &str) -> Option<Match> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(Match {
+            score: 1.,
+            positions: Vec::new(),
+        });
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().copied().map(lowercase).collect();
+
+    // Greedy from each place the first character matches, keeping the best
+    let mut best: Option<(f32, Vec<usize>)> = None;
+    for start in (0..lower.len()).filter(|i| lower[*i] == query[0]) {
+        let Some(positions) = positions_from(&lower, &query, start) else {
+            // Later starts cannot fit the query either
+            break;
+        };
+        let score = score(&chars, &positions);
+        if best.as_ref().is_none_or(|(best, _)| score > *best) {
+            best = Some((score, positions));
+        }
+    }
+
+    let (score, positions) = best?;
+    let perfect =
+        MATCH + WORD_START + TEXT_START + (query.len() - 1) as f32 * (MATCH + CONSECUTIVE);
+    Some(Match {
+        score: (score / perfect).clamp(0., 1.),
+        positions,
+    })
+}
+
+/// Positions of the query characters, matching each as early as possible
+/// after `start`. Prefers continuing a run over jumping ahead.
+fn positions_from(lower: &[char], query: &[char], start: usize) -> Option<Vec<usize>> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut next = start;
+    for c in query {
+        let pos = next + lower[next..].iter().position(|l| l == c)?;
+        positions.push(pos);
+        next = pos + 1;
+    }
+    Some(positions)
+}
+
+fn score(chars: &[char], positions: &[usize]) -> f32 {
+    let mut score = 0.;
+    let mut prev: Option<usize> = None;
+    for &pos in positions {
+        score += MATCH;
+        if pos == 0 {
+            score += TEXT_START;
+        }
+        if is_word_start(chars, pos) {
+            score += WORD_START;
+        }
+        match prev {
+            Some(prev) if pos == prev + 1 => score += CONSECUTIVE,
+            Some(prev) => score -= ((pos - prev - 1) as f32 * GAP).min(MAX_GAP_PENALTY),
+            None => {}
+        }
+        prev = Some(pos);
+    }
+    score
+}
+
+/// Lower case of `c` as a single char, so positions line up with the text.
+fn lowercase(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// First character of the text, of a word after a separator, or an upper
+/// case letter in camelCase.
+fn is_word_start(chars: &[char], pos: usize) -> bool {
+    let Some(before) = pos.checked_sub(1).map(|i| chars[i]) else {
+        return true;
+    };
+    let c = chars[pos];
+    !before.is_alphanumeric() || (before.is_lowercase() && c.is_uppercase())
+}
+
+/// Splits `text` into runs of matched and unmatched characters, for
+/// highlighting. `positions` must be sorted.
+pub fn runs<'a>(text: &'a str, positions: &[usize]) -> Vec<(&'a str, bool)> {
+    let mut runs: Vec<(&str, bool)> = Vec::new();
+    let mut run_start = 0;
+    let mut run_matched = false;
+    let mut positions = positions.iter().peekable();
+    for (idx, (byte, _)) in text.char_indices().enumerate() {
+        let matched = positions.next_if(|pos| **pos == idx).is_some();
+        if matched != run_matched {
+            if byte > run_start {
+                runs.push((&text[run_start..byte], run_matched));
+                run_start = byte;
+            }
+            run_matched = matched;
+        }
+    }
+    if run_start < text.len() {
+        runs.push((&text[run_start..], run_matched));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_starts_rank_first() {
+        let firefox = matches("ffx", "Firefox").unwrap();
+        let buffer_flux = matches("ffx", "Buffer Flux").unwrap();
+        assert!(firefox.score > buffer_flux.score);
+        assert_eq!(firefox.positions, [0, 4, 6]);
+    }
+
+    #[test]
+    fn prefix_scores_one() {
+        assert_eq!(matches("fire", "Firefox").unwrap().score, 1.);
+        assert_eq!(matches("", "Firefox").unwrap().score, 1.);
+        assert!(matches("fire fox", "Firefox").is_some());
+        assert!(matches("xf", "Firefox").is_none());
+    }
+
+    #[test]
+    fn positions_are_char_indexes() {
+        let m = matches("gr", "Größe Ärger").unwrap();
+        let chars: Vec<char> = "Größe Ärger".chars().collect();
+        let matched: String = m.positions.iter().map(|&i| chars[i]).collect();
+        assert_eq!(matched, "Gr");
+    }
+
+    #[test]
+    fn runs_split_multibyte_text() {
+        assert_eq!(
+            runs("Größe Ärger", &[2, 6, 7]),
+            [
+                ("Gr", false),
+                ("ö", true),
+                ("ße ", false),
+                ("Är", true),
+                ("ger", false)
+            ]
+        );
+        assert_eq!(
+            runs("日本語", &[1]),
+            [("日", false), ("本", true), ("語", false)]
+        );
+        assert_eq!(runs("abc", &[]), [("abc", false)]);
+        assert_eq!(runs("", &[]), []);
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! GPUs listed by switcheroo-control, for launching apps on a chosen one.
+
+use std::collections::HashMap;
+
+/// GPU reported by switcheroo-control.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GpuInfo {
+    pub name: String,
+    pub default: bool,
+    /// Environment variables that make an app render on this GPU.
+    pub environment: HashMap<String, String>,
+}
+
+/// Lists the GPUs switcheroo-control on `connection` knows about.
+pub async fn load(connection: &zbus::Connection) -> zbus::Result<Vec<GpuInfo>> {
+    let proxy = switcheroo_control::SwitcherooControlProxy::new(connection).await?;
+    Ok(proxy
+        .get_gpus()
+        .await?
+        .into_iter()
+        .map(|gpu| GpuInfo {
+            name: gpu.name,
+            default: gpu.default,
+            environment: gpu.environment,
+        })
+        .collect())
+}
+
+/// Lists the GPUs on the system bus, empty when switcheroo-control is not
+/// running.
+pub async fn load_system() -> Vec<GpuInfo> {
+    let res = match zbus::Connection::system().await {
+        Ok(connection) => load(&connection).await,
+        Err(err) => Err(err),
+    };
+    res.unwrap_or_else(|err| {
+        tracing::debug!("No GPUs from switcheroo-control: {err}");
+        Vec::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zbus::{
+        connection::Builder,
+        zvariant::{OwnedValue, Value},
+    };
+
+    /// switcheroo-control stand-in with an integrated and a discrete GPU.
+    struct Switcheroo;
+
+    #[zbus::interface(name = "net.hadess.SwitcherooControl")]
+    impl Switcheroo {
+        #[zbus(property, name = "GPUs")]
+        fn gpus(&self) -> Vec<HashMap<String, OwnedValue>> {
+            let gpu = |name: &str, environment: &[&str], default: bool| {
+                HashMap::from([
+                    ("Name".to_string(), Value::from(name).try_into().unwrap()),
+                    (
+                        "Environment".to_string(),
+                        Value::from(environment.to_vec()).try_into().unwrap(),
+                    ),
+                    (
+                        "Default".to_string(),
+                        Value::from(default).try_into().unwrap(),
+                    ),
+                ])
+            };
+            vec![
+                gpu("Intel UHD Graphics", &[], true),
+                gpu(
+                    "NVIDIA GeForce RTX 3060",
+                    &[
+                        "__NV_PRIME_RENDER_OFFLOAD",
+                        "1",
+                        "__GLX_VENDOR_LIBRARY_NAME",
+                        "nvidia",
+                    ],
+                    false,
+                ),
+            ]
+        }
+    }
+
+    #[test]
+    fn loads_gpus() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let (a, b) = tokio::net::UnixStream::pair().unwrap();
+            let (_server, client) = futures::try_join!(
+                Builder::unix_stream(a)
+                    .server(zbus::Guid::generate())
+                    .unwrap()
+                    .p2p()
+                    .serve_at("/net/hadess/SwitcherooControl", Switcheroo)
+                    .unwrap()
+                    .build(),
+                Builder::unix_stream(b).p2p().build(),
+            )
+            .unwrap();
+
+            let gpus = load(&client).await.unwrap();
+            assert_eq!(gpus.len(), 2);
+            assert_eq!(gpus[0].name, "Intel UHD Graphics");
+            assert!(gpus[0].default);
+            assert!(gpus[0].environment.is_empty());
+            assert_eq!(gpus[1].name, "NVIDIA GeForce RTX 3060");
+            assert!(!gpus[1].default);
+            assert_eq!(
+                gpus[1].environment,
+                HashMap::from([
+                    ("__NV_PRIME_RENDER_OFFLOAD".to_string(), "1".to_string()),
+                    (
+                        "__GLX_VENDOR_LIBRARY_NAME".to_string(),
+                        "nvidia".to_string()
+                    ),
+                ])
+            );
+        });
+    }
+}
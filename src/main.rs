@@ -2,12 +2,21 @@ mod components;
 #[rustfmt::skip]
 mod config;
 mod app;
+mod capabilities;
+mod capture;
+mod desktop_index;
+mod fuzzy;
+mod gpus;
 mod wayland_subscription;
 mod localize;
-mod screenshot;
-mod cosmic_workspace_capture;
+mod notifications;
+mod providers;
+mod run_command;
+mod thumbnail_cache;
 mod subscriptions;
-use tracing::info;
+mod switcher;
+mod wlr_toplevel;
+use tracing::{debug, info};
 
 use localize::localize;
 
@@ -16,18 +25,17 @@ use crate::config::VERSION;
 fn main() -> cosmic::iced::Result {
     init_logging();
 
-    println!("DEBUG: Starting cosmic-launcher");
+    debug!("Starting cosmic-launcher");
     info!(
         "cosmic-launcher ({})",
         <app::CosmicLauncher as cosmic::Application>::APP_ID
     );
     info!("Version: {} ({})", VERSION, config::profile());
-    println!("DEBUG: Version: {} ({})", VERSION, config::profile());
 
     // Prepare i18n
     localize();
 
-    println!("DEBUG: Running app");
+    debug!("Running app");
     app::run()
 }
 
@@ -0,0 +1,138 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Desktop notifications through `org.freedesktop.Notifications`.
+
+use std::collections::HashMap;
+use zbus::zvariant::Value;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+}
+
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub icon: String,
+    pub summary: String,
+    pub body: String,
+}
+
+/// Shows `notification` through the notification server on `connection`,
+/// returning the id the server gave it.
+pub async fn send(connection: &zbus::Connection, notification: &Notification) -> zbus::Result<u32> {
+    let proxy = NotificationsProxy::new(connection).await?;
+    proxy
+        .notify(
+            "COSMIC Launcher",
+            0,
+            &notification.icon,
+            &notification.summary,
+            &notification.body,
+            &[],
+            HashMap::new(),
+            -1,
+        )
+        .await
+}
+
+/// Shows `notification` on the session bus, logging failures.
+pub async fn send_session(notification: Notification) {
+    let res = match zbus::Connection::session().await {
+        Ok(connection) => send(&connection, &notification).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = res {
+        tracing::warn!("Failed to show notification {:?}: {err}", notification.summary);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use zbus::{connection::Builder, zvariant::OwnedValue};
+
+    /// Notification server stand-in recording what it was sent.
+    #[derive(Clone, Default)]
+    struct Server {
+        sent: Arc<Mutex<Vec<[String; 4]>>>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl Server {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &self,
+            app_name: String,
+            _replaces_id: u32,
+            app_icon: String,
+            summary: String,
+            body: String,
+            _actions: Vec<String>,
+            _hints: HashMap<String, OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push([app_name, app_icon, summary, body]);
+            sent.len() as u32
+        }
+    }
+
+    #[test]
+    fn sends_notification() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let server = Server::default();
+            let (a, b) = tokio::net::UnixStream::pair().unwrap();
+            let (_server_conn, client) = futures::try_join!(
+                Builder::unix_stream(a)
+                    .server(zbus::Guid::generate())
+                    .unwrap()
+                    .p2p()
+                    .serve_at("/org/freedesktop/Notifications", server.clone())
+                    .unwrap()
+                    .build(),
+                Builder::unix_stream(b).p2p().build(),
+            )
+            .unwrap();
+
+            let notification = Notification {
+                icon: "dialog-warning".into(),
+                summary: "No window appeared for Files".into(),
+                body: "It was started 10 seconds ago.".into(),
+            };
+            assert_eq!(send(&client, &notification).await.unwrap(), 1);
+            assert_eq!(send(&client, &notification).await.unwrap(), 2);
+
+            let sent = server.sent.lock().unwrap();
+            assert_eq!(
+                sent[0],
+                [
+                    "COSMIC Launcher",
+                    "dialog-warning",
+                    "No window appeared for Files",
+                    "It was started 10 seconds ago.",
+                ]
+            );
+        });
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! In-process result providers.
+//!
+//! Providers answer search queries from state the launcher already has, like
+//! its toplevel list, without a round trip through pop-launcher. Their
+//! results are merged with pop-launcher's and ranked together.
+
+mod toplevels;
+
+pub use toplevels::ToplevelProvider;
+
+use crate::desktop_index::DesktopIndex;
+use crate::wayland_subscription::{Toplevel, ToplevelHandle};
+use pop_launcher::{ContextOption, IconSource, SearchResult};
+use std::collections::HashMap;
+
+/// Provider results get ids from here up so they never collide with the
+/// indexes pop-launcher uses.
+const PROVIDER_ID_BASE: u32 = 1 << 31;
+
+/// Score of the results listed for an empty query. Nothing was matched, so
+/// they go after pop-launcher's results, which score down to 0.5.
+pub const BROWSE_SCORE: f32 = 0.5;
+
+/// Launcher state providers can search.
+pub struct ProviderContext<'a> {
+    pub toplevels: &'a [Toplevel],
+    pub desktop_index: &'a DesktopIndex,
+    /// App id of the launcher's own windows, which providers leave out.
+    pub app_id: &'a str,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProviderResult {
+    pub name: String,
+    pub description: String,
+    pub icon: Option<IconSource>,
+    /// Relevance between 0 and 1.
+    pub score: f32,
+    /// Char indexes of the characters of `name` matching the query.
+    pub name_highlights: Vec<usize>,
+    /// Char indexes of the characters of `description` matching the query.
+    pub description_highlights: Vec<usize>,
+    /// Window the result stands for, used for its thumbnail and icon.
+    pub window: Option<ToplevelHandle>,
+}
+
+/// What the launcher should do when a provider result is activated.
+#[derive(Clone, Debug)]
+pub enum ProviderAction {
+    ActivateToplevel(ToplevelHandle),
+    CloseToplevel(ToplevelHandle),
+}
+
+pub trait Provider {
+    /// Name used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Results for `query`, an empty query lists what the provider has to
+    /// offer when browsing.
+    fn query(&self, ctx: &ProviderContext, query: &str) -> Vec<ProviderResult>;
+
+    fn activate(&self, ctx: &ProviderContext, result: &ProviderResult) -> Option<ProviderAction>;
+
+    /// Secondary actions of a result, listed under it in the launcher.
+    fn context(&self, _ctx: &ProviderContext, _result: &ProviderResult) -> Vec<ContextOption> {
+        Vec::new()
+    }
+
+    fn activate_context(
+        &self,
+        _ctx: &ProviderContext,
+        _result: &ProviderResult,
+        _context: u32,
+    ) -> Option<ProviderAction> {
+        None
+    }
+
+    /// Whether pop-launcher's window results should be dropped because this
+    /// provider lists the same windows.
+    fn replaces_launcher_windows(&self) -> bool {
+        false
+    }
+}
+
+/// Provider result shown in the launcher, by its `SearchResult::id`.
+#[derive(Clone, Debug)]
+pub struct ProvidedItem {
+    pub provider: usize,
+    pub result: ProviderResult,
+}
+
+/// Ranks pop-launcher's `launcher` results together with the results of
+/// each provider, given as `(provider index, result)`.
+///
+/// pop-launcher does not report scores, its results are scored by position
+/// instead, from 0.9 for the first result down towards 0.5, so only strong
+/// provider matches go before its best results. Results with equal scores
+/// keep pop-launcher's first.
+pub fn merge(
+    launcher: Vec<SearchResult>,
+    provided: Vec<(usize, ProviderResult)>,
+    drop_launcher_windows: bool,
+) -> (Vec<SearchResult>, HashMap<u32, ProvidedItem>) {
+    let launcher: Vec<_> = launcher
+        .into_iter()
+        .filter(|result| !(drop_launcher_windows && result.window.is_some()))
+        .collect();
+    let len = launcher.len() as f32;
+    let mut ranked: Vec<(f32, SearchResult)> = launcher
+        .into_iter()
+        .enumerate()
+        .map(|(pos, result)| (0.9 - 0.4 * pos as f32 / len, result))
+        .collect();
+
+    let mut items = HashMap::new();
+    for (n, (provider, result)) in provided.into_iter().enumerate() {
+        let id = PROVIDER_ID_BASE + n as u32;
+        let item = SearchResult {
+            id,
+            name: result.name.clone(),
+            description: result.description.clone(),
+            icon: result.icon.clone(),
+            category_icon: None,
+            window: None,
+        };
+        ranked.push((result.score, item));
+        items.insert(id, ProvidedItem { provider, result });
+    }
+
+    // Stable, so ties keep pop-launcher results first
+    ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    (ranked.into_iter().map(|(_, item)| item).collect(), items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launcher_result(id: u32, name: &str) -> SearchResult {
+        SearchResult {
+            id,
+            name: name.to_string(),
+            description: String::new(),
+            icon: None,
+            category_icon: None,
+            window: None,
+        }
+    }
+
+    fn provided(name: &str, score: f32) -> ProviderResult {
+        ProviderResult {
+            name: name.to_string(),
+            description: String::new(),
+            icon: None,
+            score,
+            name_highlights: Vec::new(),
+            description_highlights: Vec::new(),
+            window: None,
+        }
+    }
+
+    fn names(items: &[SearchResult]) -> Vec<&str> {
+        items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn launcher_results_score_by_position() {
+        let launcher = vec![
+            launcher_result(0, "a"),
+            launcher_result(1, "b"),
+            launcher_result(2, "c"),
+            launcher_result(3, "d"),
+        ];
+        // 0.9, 0.8, 0.7 and 0.6 by position
+        let (items, _) = merge(launcher, vec![(0, provided("x", 0.75))], false);
+        assert_eq!(names(&items), ["a", "b", "x", "c", "d"]);
+    }
+
+    #[test]
+    fn interleaves_by_score() {
+        let launcher = vec![launcher_result(0, "a"), launcher_result(1, "b")];
+        let provided = vec![
+            (0, provided("low", 0.1)),
+            (1, provided("best", 1.0)),
+            // Ties go after pop-launcher's result
+            (0, provided("tie", 0.9)),
+        ];
+        let (items, _) = merge(launcher, provided, false);
+        assert_eq!(names(&items), ["best", "a", "tie", "b", "low"]);
+    }
+
+    #[test]
+    fn provider_ids_start_at_base() {
+        let launcher = vec![launcher_result(0, "a")];
+        let provided = vec![(0, provided("x", 0.2)), (1, provided("y", 0.1))];
+        let (items, provided) = merge(launcher, provided, false);
+        let ids: Vec<u32> = items.iter().map(|item| item.id).collect();
+        assert_eq!(ids, [0, PROVIDER_ID_BASE, PROVIDER_ID_BASE + 1]);
+        assert_eq!(provided[&PROVIDER_ID_BASE].result.name, "x");
+        assert_eq!(provided[&(PROVIDER_ID_BASE + 1)].provider, 1);
+        assert!(!provided.contains_key(&0));
+    }
+
+    #[test]
+    fn browsing_lists_launcher_results_first() {
+        let launcher: Vec<_> = (0..10).map(|id| launcher_result(id, "app")).collect();
+        let provided = vec![(0, provided("window", BROWSE_SCORE))];
+        let (items, _) = merge(launcher, provided, false);
+        assert_eq!(items.last().unwrap().name, "window");
+    }
+}
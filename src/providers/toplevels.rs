@@ -0,0 +1,160 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Open windows, fuzzy searched by title, app name and app id.
+
+use super::{BROWSE_SCORE, Provider, ProviderAction, ProviderContext, ProviderResult};
+use crate::fuzzy;
+use pop_launcher::{ContextOption, IconSource};
+
+const CLOSE: u32 = 0;
+
+pub struct ToplevelProvider;
+
+impl Provider for ToplevelProvider {
+    fn name(&self) -> &'static str {
+        "toplevels"
+    }
+
+    fn query(&self, ctx: &ProviderContext, query: &str) -> Vec<ProviderResult> {
+        ctx.toplevels
+            .iter()
+            .filter(|toplevel| toplevel.app_id != ctx.app_id)
+            .filter_map(|toplevel| {
+                let app_name = ctx
+                    .desktop_index
+                    .lookup(&toplevel.app_id)
+                    .map_or(toplevel.app_id.as_str(), |app| app.name.as_str());
+                let name = if toplevel.title.is_empty() {
+                    app_name
+                } else {
+                    toplevel.title.as_str()
+                };
+                let by_name = fuzzy::matches(query, name);
+                let by_app = fuzzy::matches(query, app_name);
+                let by_id = fuzzy::matches(query, &toplevel.app_id);
+                let score = [&by_name, &by_app, &by_id]
+                    .into_iter()
+                    .flatten()
+                    .map(|m| m.score)
+                    .reduce(f32::max)?;
+                // Every window matches an empty query equally
+                let score = if query.is_empty() {
+                    BROWSE_SCORE
+                } else {
+                    score
+                };
+                Some(ProviderResult {
+                    name: name.to_string(),
+                    description: app_name.to_string(),
+                    icon: Some(IconSource::Name(toplevel.app_id.clone().into())),
+                    score,
+                    name_highlights: by_name.map(|m| m.positions).unwrap_or_default(),
+                    description_highlights: by_app.map(|m| m.positions).unwrap_or_default(),
+                    window: Some(toplevel.handle.clone()),
+                })
+            })
+            .collect()
+    }
+
+    fn activate(&self, _ctx: &ProviderContext, result: &ProviderResult) -> Option<ProviderAction> {
+        result.window.clone().map(ProviderAction::ActivateToplevel)
+    }
+
+    fn context(&self, _ctx: &ProviderContext, _result: &ProviderResult) -> Vec<ContextOption> {
+        vec![ContextOption {
+            id: CLOSE,
+            name: "Close window".to_string(),
+        }]
+    }
+
+    fn activate_context(
+        &self,
+        _ctx: &ProviderContext,
+        result: &ProviderResult,
+        context: u32,
+    ) -> Option<ProviderAction> {
+        match context {
+            CLOSE => result.window.clone().map(ProviderAction::CloseToplevel),
+            _ => None,
+        }
+    }
+
+    fn replaces_launcher_windows(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::desktop_index::DesktopIndex;
+    use crate::wayland_subscription::{Toplevel, ToplevelHandle};
+    use cosmic::cctk::wayland_client::{Connection, Proxy};
+    use cosmic::cctk::wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1;
+
+    const LAUNCHER_ID: &str = "com.system76.CosmicLauncher";
+
+    fn toplevel(title: &str, app_id: &str) -> Toplevel {
+        // Never sent to a compositor, an inert handle will do
+        let (socket, _) = std::os::unix::net::UnixStream::pair().unwrap();
+        let connection = Connection::from_socket(socket).unwrap();
+        let handle = ExtForeignToplevelHandleV1::inert(connection.backend().downgrade());
+        Toplevel {
+            handle: ToplevelHandle::Ext(handle),
+            title: title.to_string(),
+            app_id: app_id.to_string(),
+            state: Default::default(),
+            outputs: Default::default(),
+            geometry: Default::default(),
+            workspaces: Default::default(),
+            activation_token: None,
+        }
+    }
+
+    fn query(toplevels: &[Toplevel], query: &str) -> Vec<ProviderResult> {
+        let desktop_index = DesktopIndex::default();
+        let ctx = ProviderContext {
+            toplevels,
+            desktop_index: &desktop_index,
+            app_id: LAUNCHER_ID,
+        };
+        ToplevelProvider.query(&ctx, query)
+    }
+
+    #[test]
+    fn leaves_out_launcher_windows() {
+        let toplevels = [
+            toplevel("Launcher", LAUNCHER_ID),
+            toplevel("Inbox", "org.gnome.Evolution"),
+        ];
+        let names: Vec<_> = query(&toplevels, "")
+            .into_iter()
+            .map(|result| result.name)
+            .collect();
+        assert_eq!(names, ["Inbox"]);
+    }
+
+    #[test]
+    fn matches_title_and_app_id() {
+        let toplevels = [
+            toplevel("Mozilla Firefox", "firefox"),
+            toplevel("Terminal", "org.gnome.Ptyxis"),
+        ];
+        let results = query(&toplevels, "ptyx");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Terminal");
+        assert!(results[0].name_highlights.is_empty());
+
+        let results = query(&toplevels, "fire");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name_highlights, [8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn empty_query_scores_neutral() {
+        let toplevels = [toplevel("Mozilla Firefox", "firefox")];
+        assert_eq!(query(&toplevels, "")[0].score, BROWSE_SCORE);
+        assert!(query(&toplevels, "mozilla")[0].score > BROWSE_SCORE);
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Run mode: input starting with `>` runs a command, `!` runs it in a
+//! terminal. Commands are completed from `PATH` and remembered in the XDG
+//! state directory.
+
+use std::{
+    collections::BTreeSet,
+    os::unix::fs::PermissionsExt,
+    path::PathBuf,
+};
+
+const HISTORY_FILE: &str = "run-history";
+const HISTORY_LEN: usize = 100;
+const MAX_SUGGESTIONS: usize = 20;
+
+/// Search input in run mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunInput<'a> {
+    pub command: &'a str,
+    pub terminal: bool,
+}
+
+/// Splits the run mode prefix off `input`, `None` outside run mode.
+pub fn parse(input: &str) -> Option<RunInput<'_>> {
+    let input = input.trim_start();
+    if let Some(command) = input.strip_prefix('>') {
+        Some(RunInput {
+            command: command.trim(),
+            terminal: false,
+        })
+    } else {
+        input.strip_prefix('!').map(|command| RunInput {
+            command: command.trim(),
+            terminal: true,
+        })
+    }
+}
+
+/// Previously run commands, most recent first.
+#[derive(Debug, Default)]
+pub struct History {
+    path: Option<PathBuf>,
+    entries: Vec<String>,
+}
+
+impl History {
+    pub fn load() -> Self {
+        let path = xdg::BaseDirectories::with_prefix("cosmic-launcher")
+            .ok()
+            .and_then(|dirs| dirs.place_state_file(HISTORY_FILE).ok());
+        let entries = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|history| history.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        Self { path, entries }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Moves `command` to the front. The returned future saves the history
+    /// on a blocking thread.
+    pub fn push(&mut self, command: &str) -> impl Future<Output = ()> + use<> {
+        self.entries.retain(|entry| entry != command);
+        self.entries.insert(0, command.to_string());
+        self.entries.truncate(HISTORY_LEN);
+
+        let save = self
+            .path
+            .clone()
+            .map(|path| (path, self.entries.join("\n")));
+        async move {
+            let Some((path, history)) = save else {
+                return;
+            };
+            let _ = tokio::task::spawn_blocking(move || {
+                if let Err(err) = std::fs::write(&path, history) {
+                    tracing::warn!("Failed to save run history to {}: {err}", path.display());
+                }
+            })
+            .await;
+        }
+    }
+}
+
+/// Names of the executables in `PATH`, sorted.
+pub fn path_executables() -> Vec<String> {
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    executables_in(std::env::split_paths(&path))
+}
+
+/// Names of the executables in `dirs`, sorted. Symlinks count when their
+/// target is an executable file, like the alternatives in `/usr/bin`.
+fn executables_in(dirs: impl IntoIterator<Item = PathBuf>) -> Vec<String> {
+    let mut executables = BTreeSet::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let executable = std::fs::metadata(entry.path())
+                .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0);
+            if let (true, Ok(name)) = (executable, entry.file_name().into_string()) {
+                executables.insert(name);
+            }
+        }
+    }
+    executables.into_iter().collect()
+}
+
+/// History entries starting with `command`, then executables completing it
+/// while only the program name has been typed.
+pub fn suggestions(history: &History, executables: &[String], command: &str) -> Vec<String> {
+    let mut suggestions: Vec<String> = history
+        .entries()
+        .iter()
+        .filter(|entry| entry.starts_with(command))
+        .take(MAX_SUGGESTIONS / 2)
+        .cloned()
+        .collect();
+    if !command.is_empty() && !command.contains(char::is_whitespace) {
+        let start = executables.partition_point(|name| name.as_str() < command);
+        let completions: Vec<_> = executables[start..]
+            .iter()
+            .take_while(|name| name.starts_with(command))
+            .filter(|name| !suggestions.contains(name))
+            .take(MAX_SUGGESTIONS - suggestions.len())
+            .cloned()
+            .collect();
+        suggestions.extend(completions);
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn history(entries: &[&str]) -> History {
+        History {
+            path: None,
+            entries: entries.iter().map(|entry| entry.to_string()).collect(),
+        }
+    }
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_prefixes() {
+        let run = |command, terminal| Some(RunInput { command, terminal });
+        assert_eq!(parse(">ls -la "), run("ls -la", false));
+        assert_eq!(parse("  ! htop"), run("htop", true));
+        assert_eq!(parse(">"), run("", false));
+        assert_eq!(parse("firefox"), None);
+        assert_eq!(parse("a > b"), None);
+    }
+
+    #[test]
+    fn push_moves_to_front() {
+        let mut history = history(&["ls", "htop", "top"]);
+        drop(history.push("top"));
+        assert_eq!(history.entries(), strings(&["top", "ls", "htop"]));
+
+        for n in 0..HISTORY_LEN {
+            drop(history.push(&format!("echo {n}")));
+        }
+        assert_eq!(history.entries().len(), HISTORY_LEN);
+        assert_eq!(history.entries()[0], format!("echo {}", HISTORY_LEN - 1));
+    }
+
+    #[test]
+    fn suggests_history_then_executables() {
+        let history = history(&["git status", "gimp", "ls"]);
+        let executables = strings(&["gedit", "gimp", "git", "gitk", "ls"]);
+        assert_eq!(
+            suggestions(&history, &executables, "gi"),
+            strings(&["git status", "gimp", "git", "gitk"])
+        );
+        // Only history once arguments are typed
+        assert_eq!(
+            suggestions(&history, &executables, "git s"),
+            strings(&["git status"])
+        );
+        assert_eq!(
+            suggestions(&history, &executables, ""),
+            strings(&["git status", "gimp", "ls"])
+        );
+        assert!(suggestions(&history, &executables, "xyz").is_empty());
+    }
+
+    #[test]
+    fn suggestions_are_capped() {
+        let history = history(&[]);
+        let executables: Vec<_> = (0..50).map(|n| format!("x{n:02}")).collect();
+        let suggestions = suggestions(&history, &executables, "x");
+        assert_eq!(suggestions.len(), MAX_SUGGESTIONS);
+        assert_eq!(suggestions[0], "x00");
+    }
+
+    #[test]
+    fn lists_executables_through_symlinks() {
+        let dir = std::env::temp_dir().join(format!("cosmic-launcher-path-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("subdir")).unwrap();
+        let write = |name: &str, mode: u32| {
+            fs::write(dir.join(name), "").unwrap();
+            fs::set_permissions(dir.join(name), fs::Permissions::from_mode(mode)).unwrap();
+        };
+        write("tool", 0o755);
+        write("data", 0o644);
+        std::os::unix::fs::symlink(dir.join("tool"), dir.join("tool-link")).unwrap();
+        std::os::unix::fs::symlink(dir.join("data"), dir.join("data-link")).unwrap();
+        std::os::unix::fs::symlink(dir.join("missing"), dir.join("dangling")).unwrap();
+
+        assert_eq!(
+            executables_in([dir.clone(), dir.join("missing")]),
+            strings(&["tool", "tool-link"])
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,34 +0,0 @@
-#![allow(dead_code)]
-
-use crate::cosmic_workspace_capture::create_toplevel_capture_element;
-
-// Simplified screenshot manager - only uses COSMIC native capture
-#[derive(Clone)]
-pub struct ScreenshotManager;
-
-impl ScreenshotManager {
-    pub fn new() -> Self {
-        println!("🚀 Initializing COSMIC native capture system");
-        Self
-    }
-
-    // Only method needed - creates native COSMIC capture elements
-    pub fn create_capture_element(&self, title: &str) -> cosmic::Element<'static, crate::app::Message> {
-        create_toplevel_capture_element(title)
-    }
-}
-
-impl Default for ScreenshotManager {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-// Create cosmic image handle from COSMIC capture (simplified)
-pub fn create_cosmic_image_handle(title: &str) -> Result<cosmic::widget::image::Handle, Box<dyn std::error::Error>> {
-    println!("🖼️ Creating COSMIC image handle for: '{}'", title);
-    
-    // For now, return a placeholder - real implementation would use COSMIC compositor
-    let placeholder_data = vec![0, 0, 0, 0]; // RGBA transparent
-    Ok(cosmic::widget::image::Handle::from_bytes(placeholder_data))
-}
\ No newline at end of file
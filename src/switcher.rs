@@ -0,0 +1,165 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Selection logic of the window switcher, kept apart from the app state so
+//! it can be tested without a compositor.
+
+use crate::fuzzy;
+
+/// What a character typed while the switcher is open does.
+#[derive(Debug, PartialEq)]
+pub enum CharacterAction {
+    /// Add it to the filter
+    Type,
+    JumpToUrgent,
+    /// Send the selected window to this workspace index
+    MoveToWorkspace(usize),
+}
+
+/// Action of character `c` typed with Alt or Shift held. Alt+U and
+/// Shift+1..9 are shortcuts until a filter was typed, after that every
+/// character goes to the filter so it can contain them.
+pub fn character_action(c: &str, alt: bool, shift: bool, filtering: bool) -> CharacterAction {
+    if filtering {
+        return CharacterAction::Type;
+    }
+    if c == "u" && alt {
+        return CharacterAction::JumpToUrgent;
+    }
+    match c.parse::<usize>() {
+        Ok(n @ 1..=9) if shift => CharacterAction::MoveToWorkspace(n - 1),
+        _ => CharacterAction::Type,
+    }
+}
+
+/// How well a window matches `filter` by title or app name, `None` when it
+/// is filtered out.
+pub fn filter_score(filter: &str, title: &str, app_name: Option<&str>) -> Option<f32> {
+    [Some(title), app_name]
+        .into_iter()
+        .flatten()
+        .filter_map(|text| fuzzy::matches(filter, text))
+        .map(|m| m.score)
+        .reduce(f32::max)
+}
+
+/// Orders the windows left by the filter, given as `(score, minimized,
+/// item)`, best match first. Minimized windows go after the visible ones
+/// with the same score, otherwise the order is kept.
+pub fn filter_order<T>(mut items: Vec<(f32, bool, T)>) -> Vec<T> {
+    items.sort_by(|(a, a_min, _), (b, b_min, _)| b.total_cmp(a).then(a_min.cmp(b_min)));
+    items.into_iter().map(|(_, _, item)| item).collect()
+}
+
+/// Index to select after the switcher items were rebuilt.
+///
+/// `items` identifies each new item, `selected` is the item selected before
+/// and `previous` its index. The selection follows `selected` wherever it
+/// ended up. When it is gone the item now at its old position is selected,
+/// or the last one when it was at the end.
+pub fn reselect<K: PartialEq>(
+    items: &[K],
+    selected: Option<&K>,
+    previous: Option<usize>,
+) -> Option<usize> {
+    if items.is_empty() {
+        return None;
+    }
+    selected
+        .and_then(|selected| items.iter().position(|item| item == selected))
+        .or(previous.map(|i| i.min(items.len() - 1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removing_selected_selects_next() {
+        assert_eq!(reselect(&["a", "c", "d"], Some(&"b"), Some(1)), Some(1));
+    }
+
+    #[test]
+    fn removing_selected_last_selects_new_last() {
+        assert_eq!(reselect(&["a", "b"], Some(&"c"), Some(2)), Some(1));
+        assert_eq!(reselect::<&str>(&[], Some(&"a"), Some(0)), None);
+    }
+
+    #[test]
+    fn removing_before_selection_follows_it() {
+        assert_eq!(reselect(&["b", "c", "d"], Some(&"c"), Some(2)), Some(1));
+    }
+
+    #[test]
+    fn keeps_selection_when_moved() {
+        assert_eq!(reselect(&["c", "a", "b"], Some(&"b"), Some(1)), Some(2));
+        assert_eq!(reselect(&["a", "b"], None, None), None);
+    }
+
+    #[test]
+    fn shortcuts_until_filtering() {
+        assert_eq!(
+            character_action("u", true, false, false),
+            CharacterAction::JumpToUrgent
+        );
+        assert_eq!(
+            character_action("3", false, true, false),
+            CharacterAction::MoveToWorkspace(2)
+        );
+        assert_eq!(
+            character_action("u", true, false, true),
+            CharacterAction::Type
+        );
+        assert_eq!(
+            character_action("3", false, true, true),
+            CharacterAction::Type
+        );
+    }
+
+    #[test]
+    fn other_characters_type() {
+        assert_eq!(
+            character_action("u", false, false, false),
+            CharacterAction::Type
+        );
+        assert_eq!(
+            character_action("3", false, false, false),
+            CharacterAction::Type
+        );
+        assert_eq!(
+            character_action("0", false, true, false),
+            CharacterAction::Type
+        );
+        assert_eq!(
+            character_action("f", true, false, false),
+            CharacterAction::Type
+        );
+    }
+
+    #[test]
+    fn filter_score_takes_best_of_title_and_app() {
+        assert_eq!(filter_score("", "Inbox", None), Some(1.0));
+        assert!(filter_score("fire", "Mozilla", Some("Firefox")).is_some());
+        assert!(filter_score("term", "Inbox", Some("Firefox")).is_none());
+        let title = filter_score("fire", "Fire safety", None).unwrap();
+        let both = filter_score("fire", "Fire safety", Some("Firefox")).unwrap();
+        assert!(both >= title);
+    }
+
+    #[test]
+    fn filter_order_puts_minimized_last_among_equals() {
+        let items = vec![
+            (1.0, true, "a"),
+            (1.0, false, "b"),
+            (0.5, false, "c"),
+            (1.0, false, "d"),
+        ];
+        assert_eq!(filter_order(items), ["b", "d", "a", "c"]);
+    }
+
+    #[test]
+    fn filter_order_prefers_better_match_over_minimized() {
+        let items = vec![(0.2, false, "a"), (0.9, true, "b")];
+        assert_eq!(filter_order(items), ["b", "a"]);
+    }
+}
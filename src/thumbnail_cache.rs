@@ -0,0 +1,194 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Bounded cache for toplevel thumbnails.
+//!
+//! Thumbnails are kept until the total size of their pixel buffers exceeds the
+//! byte budget, at which point the least recently used ones are evicted.
+
+use crate::wayland_subscription::WaylandImage;
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+/// Default memory budget for cached thumbnails.
+pub const DEFAULT_BUDGET: usize = 64 * 1024 * 1024;
+
+struct Entry {
+    image: WaylandImage,
+    captured: Instant,
+    last_used: Cell<u64>,
+}
+
+pub struct ThumbnailCache<K> {
+    entries: HashMap<K, Entry>,
+    budget: usize,
+    bytes: usize,
+    clock: Cell<u64>,
+}
+
+impl<K: Eq + Hash + Clone> ThumbnailCache<K> {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            budget,
+            bytes: 0,
+            clock: Cell::new(0),
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        let now = self.clock.get() + 1;
+        self.clock.set(now);
+        now
+    }
+
+    /// Returns the thumbnail for `key` and marks it as recently used.
+    pub fn get(&self, key: &K) -> Option<&WaylandImage> {
+        let entry = self.entries.get(key)?;
+        entry.last_used.set(self.tick());
+        Some(&entry.image)
+    }
+
+    /// Stores a new thumbnail for `key`, evicting the least recently used
+    /// thumbnails until the cache fits its budget again.
+    pub fn insert(&mut self, key: K, image: WaylandImage) {
+        let entry = Entry {
+            captured: Instant::now(),
+            last_used: Cell::new(self.tick()),
+            image,
+        };
+        self.bytes += entry.image.img.len();
+        if let Some(old) = self.entries.insert(key.clone(), entry) {
+            self.bytes -= old.image.img.len();
+        }
+
+        while self.bytes > self.budget {
+            let Some(lru) = self
+                .entries
+                .iter()
+                .filter(|(k, _)| **k != key)
+                .min_by_key(|(_, entry)| entry.last_used.get())
+                .map(|(k, _)| k.clone())
+            else {
+                // Only the new thumbnail is left, keep it even if it is too big
+                break;
+            };
+            tracing::debug!("Evicting thumbnail to stay within {} bytes", self.budget);
+            self.remove(&lru);
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<WaylandImage> {
+        let entry = self.entries.remove(key)?;
+        self.bytes -= entry.image.img.len();
+        Some(entry.image)
+    }
+
+    /// Drops every thumbnail whose key does not satisfy `keep`.
+    pub fn retain<F: Fn(&K) -> bool>(&mut self, keep: F) {
+        self.entries.retain(|key, _| keep(key));
+        self.bytes = self.entries.values().map(|entry| entry.image.img.len()).sum();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+    }
+
+    /// Whether the thumbnail for `key` is missing or older than `max_age`.
+    pub fn is_stale(&self, key: &K, max_age: Duration) -> bool {
+        self.entries
+            .get(key)
+            .is_none_or(|entry| entry.captured.elapsed() >= max_age)
+    }
+
+    /// Total size of the cached pixel buffers in bytes.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(len: usize) -> WaylandImage {
+        WaylandImage {
+            img: vec![0; len].into(),
+            width: 1,
+            height: 1,
+        }
+    }
+
+    fn cache_abc() -> ThumbnailCache<&'static str> {
+        let mut cache = ThumbnailCache::new(300);
+        cache.insert("a", image(100));
+        cache.insert("b", image(100));
+        cache.insert("c", image(100));
+        cache
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = cache_abc();
+        assert_eq!(cache.bytes(), 300);
+        cache.insert("d", image(100));
+        assert!(cache.get(&"a").is_none());
+        assert!(cache.get(&"b").is_some());
+        assert!(cache.get(&"d").is_some());
+        assert_eq!(cache.bytes(), 300);
+
+        // Evicts as many as needed to fit a bigger thumbnail
+        cache.insert("e", image(250));
+        assert_eq!(cache.bytes(), 250);
+        assert!(cache.get(&"e").is_some());
+    }
+
+    #[test]
+    fn get_refreshes_recency() {
+        let mut cache = cache_abc();
+        assert!(cache.get(&"a").is_some());
+        cache.insert("d", image(100));
+        assert!(cache.get(&"a").is_some());
+        assert!(cache.get(&"b").is_none());
+    }
+
+    #[test]
+    fn replacing_keeps_byte_count() {
+        let mut cache = cache_abc();
+        cache.insert("b", image(50));
+        assert_eq!(cache.bytes(), 250);
+    }
+
+    #[test]
+    fn remove_frees_budget() {
+        let mut cache = cache_abc();
+        assert_eq!(cache.remove(&"b").map(|image| image.img.len()), Some(100));
+        assert_eq!(cache.bytes(), 200);
+        cache.insert("d", image(100));
+        assert!(["a", "c", "d"].iter().all(|key| cache.get(key).is_some()));
+    }
+
+    #[test]
+    fn keeps_single_thumbnail_over_budget() {
+        let mut cache = ThumbnailCache::new(100);
+        cache.insert("a", image(50));
+        cache.insert("b", image(200));
+        assert!(cache.get(&"a").is_none());
+        assert!(cache.get(&"b").is_some());
+        assert_eq!(cache.bytes(), 200);
+    }
+
+    #[test]
+    fn is_stale_honours_max_age() {
+        let mut cache = ThumbnailCache::new(300);
+        assert!(cache.is_stale(&"a", Duration::from_secs(60)));
+        cache.insert("a", image(100));
+        assert!(!cache.is_stale(&"a", Duration::from_secs(60)));
+        assert!(cache.is_stale(&"a", Duration::ZERO));
+    }
+}
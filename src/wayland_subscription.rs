@@ -8,17 +8,30 @@
 
 use cosmic::{
     cctk::{
-        screencopy::{CaptureFrame, CaptureOptions, CaptureSession, CaptureSource, Capturer, FailureReason, 
-                     Formats, Frame, ScreencopyFrameData, ScreencopyFrameDataExt, ScreencopyHandler,
-                     ScreencopySessionData, ScreencopySessionDataExt, ScreencopyState},
+        screencopy::{self as cctk_screencopy, CaptureFrame, CaptureOptions, CaptureSession, Capturer,
+                     FailureReason, Formats, Frame, ScreencopyFrameData, ScreencopyFrameDataExt,
+                     ScreencopyHandler, ScreencopySessionData, ScreencopySessionDataExt, ScreencopyState},
         toplevel_info::{ToplevelInfo, ToplevelInfoHandler, ToplevelInfoState},
+        toplevel_management::{ToplevelManagerHandler, ToplevelManagerState},
+        workspace::{WorkspaceHandler, WorkspaceState},
+        cosmic_protocols::{
+            toplevel_info::v1::client::zcosmic_toplevel_handle_v1,
+            toplevel_management::v1::client::zcosmic_toplevel_manager_v1,
+        },
         wayland_client::{
             globals::registry_queue_init,
-            protocol::{wl_buffer, wl_shm, wl_shm_pool},
-            Connection, QueueHandle, Dispatch, WEnum,
+            protocol::{wl_buffer, wl_output::{self, WlOutput}, wl_shm, wl_shm_pool},
+            Connection, Proxy, QueueHandle, Dispatch, WEnum,
+        },
+        wayland_protocols::ext::{
+            foreign_toplevel_list::v1::client::ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1,
+            workspace::v1::client::{
+                ext_workspace_group_handle_v1::ExtWorkspaceGroupHandleV1,
+                ext_workspace_handle_v1::{self, ExtWorkspaceHandleV1},
+            },
         },
-        wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1,
         sctk::{
+            output::{OutputHandler, OutputState},
             registry::{ProvidesRegistryState, RegistryState},
             seat::{SeatHandler, SeatState},
             shm::{Shm, ShmHandler},
@@ -27,18 +40,35 @@ use cosmic::{
     iced::{self, stream, Subscription},
     iced_core::image::Bytes,
 };
+use crate::{
+    capabilities::{Capabilities, ToplevelBackend},
+    capture::{
+        self, CaptureBackend, CaptureBackendKind, CaptureCapabilities, CaptureSource, Session,
+        ShmImage, SHM_FORMATS,
+    },
+    wlr_toplevel::{WlrToplevelData, WlrToplevelHandler, WlrToplevelState},
+};
+use calloop_wayland_source::WaylandSource;
 use futures::{
     channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
     SinkExt, StreamExt,
 };
-use image::EncodableLayout;
 use once_cell::sync::Lazy;
 use std::{
-    fmt::Debug,
+    collections::{HashMap, HashSet},
+    fmt::{self, Debug},
     os::fd::AsFd,
-    sync::{Arc, Condvar, Mutex, MutexGuard},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 use tokio::sync::Mutex as TokioMutex;
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
+    zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1,
+};
+
+/// How long a single capture may take before it is abandoned.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub static WAYLAND_RX: Lazy<TokioMutex<Option<UnboundedReceiver<WaylandUpdate>>>> =
     Lazy::new(|| TokioMutex::new(None));
@@ -52,11 +82,12 @@ pub struct WaylandImage {
 
 impl WaylandImage {
     pub fn new(img: image::RgbaImage) -> Self {
+        let (width, height) = img.dimensions();
         Self {
-            // TODO avoid copy?
-            img: Bytes::copy_from_slice(img.as_bytes()),
-            width: img.width(),
-            height: img.height(),
+            // Hand the pixel buffer over without copying it
+            img: Bytes::from(img.into_raw()),
+            width,
+            height,
         }
     }
 }
@@ -69,24 +100,247 @@ impl AsRef<[u8]> for WaylandImage {
 
 #[derive(Clone, Debug)]
 pub enum WaylandUpdate {
-    Init,
+    Init(calloop::channel::Sender<WaylandRequest>),
     Finished,
     Toplevel(ToplevelUpdate),
-    Image(ExtForeignToplevelHandleV1, WaylandImage),
+    Image(ToplevelHandle, WaylandImage),
+    CaptureFailed(ToplevelHandle, CaptureError),
+    /// Every workspace, sent whenever the compositor finishes a batch of changes.
+    Workspaces(Vec<WorkspaceInfo>),
+    /// Capture of the output showing the workspace.
+    WorkspaceImage(ExtWorkspaceHandleV1, WaylandImage),
+    /// Protocols found on the compositor, sent once after connecting.
+    Capabilities(Capabilities),
+    /// The handler thread hit an error and is going to be restarted.
+    Error(WaylandError),
+}
+
+/// Reason the wayland handler thread stopped.
+#[derive(Clone, Debug)]
+pub enum WaylandError {
+    /// Connecting to the compositor failed.
+    Connect(String),
+    /// The initial registry roundtrip failed.
+    Registry(String),
+    /// A required global is not advertised by the compositor.
+    MissingGlobal(&'static str),
+    /// The calloop event loop could not be set up.
+    EventLoop(String),
+    /// Dispatching wayland events failed, usually because the connection was lost.
+    Dispatch(String),
+}
+
+impl fmt::Display for WaylandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaylandError::Connect(err) => write!(f, "failed to connect to wayland: {err}"),
+            WaylandError::Registry(err) => write!(f, "failed to get wayland globals: {err}"),
+            WaylandError::MissingGlobal(global) => write!(f, "missing wayland global {global}"),
+            WaylandError::EventLoop(err) => write!(f, "failed to set up event loop: {err}"),
+            WaylandError::Dispatch(err) => write!(f, "wayland dispatch failed: {err}"),
+        }
+    }
+}
+
+/// Reason a toplevel capture did not produce a thumbnail.
+#[derive(Clone, Debug)]
+pub enum CaptureError {
+    /// The compositor refused to create a capture session.
+    Session,
+    /// Flushing requests to the compositor failed.
+    Connection,
+    /// None of the offered shm formats can be converted to RGBA.
+    UnsupportedFormat,
+    /// The capture backend cannot capture this kind of source.
+    UnsupportedSource,
+    /// The shm buffer could not be allocated.
+    Buffer,
+    /// The compositor did not answer before the deadline.
+    Timeout,
+    /// The toplevel was closed while it was being captured.
+    Cancelled,
+    /// The compositor stopped the capture session.
+    Stopped,
+    /// The compositor reported that copying the frame failed.
+    Failed(WEnum<FailureReason>),
+    /// The captured buffer could not be converted to an image.
+    Image(String),
+    /// The backend found no window or output matching the source.
+    NotFound,
+    /// The backend's own capture call failed.
+    Backend(String),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::Session => write!(f, "failed to create capture session"),
+            CaptureError::Connection => write!(f, "failed to flush wayland connection"),
+            CaptureError::UnsupportedFormat => write!(f, "no supported shm format"),
+            CaptureError::UnsupportedSource => write!(f, "source cannot be captured by this backend"),
+            CaptureError::Buffer => write!(f, "failed to allocate shm buffer"),
+            CaptureError::Timeout => write!(f, "capture timed out"),
+            CaptureError::Cancelled => write!(f, "capture cancelled"),
+            CaptureError::Stopped => write!(f, "capture session stopped"),
+            CaptureError::Failed(reason) => write!(f, "capture failed: {reason:?}"),
+            CaptureError::Image(err) => write!(f, "failed to convert capture: {err}"),
+            CaptureError::NotFound => write!(f, "no matching window or output"),
+            CaptureError::Backend(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// Handle of a toplevel on whichever protocol the compositor supports.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ToplevelHandle {
+    Ext(ExtForeignToplevelHandleV1),
+    Wlr(ZwlrForeignToplevelHandleV1),
+}
+
+/// What an in-flight capture is for, to cancel it when that goes away.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum CaptureKey {
+    Toplevel(ToplevelHandle),
+    Workspace(ExtWorkspaceHandleV1),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ToplevelState {
+    Activated,
+    Maximized,
+    Minimized,
+    Fullscreen,
+    /// The toplevel probably asked for attention. Neither toplevel protocol
+    /// reports this per window, only workspaces are marked urgent, so it is
+    /// only set on the one unfocused toplevel of an urgent workspace. With
+    /// several candidates only `WorkspaceInfo::urgent` is known.
+    Urgent,
+}
+
+/// Backend independent description of a toplevel.
+#[derive(Clone, Debug)]
+pub struct Toplevel {
+    pub handle: ToplevelHandle,
+    pub title: String,
+    pub app_id: String,
+    pub state: HashSet<ToplevelState>,
+    pub outputs: HashSet<WlOutput>,
+    /// Position of the toplevel on each output it is shown on. Only reported
+    /// by the cosmic backend.
+    pub geometry: HashMap<WlOutput, ToplevelGeometry>,
+    pub workspaces: HashSet<ExtWorkspaceHandleV1>,
+    /// XDG activation token the toplevel's client was started with. None of
+    /// the toplevel protocols report it yet, launches are matched by app id
+    /// while it is `None`.
+    pub activation_token: Option<String>,
+}
+
+/// Position and size of a toplevel relative to an output, in logical pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ToplevelGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Toplevel {
+    /// Geometry on the output showing the largest part of the toplevel.
+    pub fn geometry(&self) -> Option<(&WlOutput, ToplevelGeometry)> {
+        self.geometry
+            .iter()
+            .max_by_key(|(_, geometry)| geometry.width as i64 * geometry.height as i64)
+            .map(|(output, geometry)| (output, *geometry))
+    }
+}
+
+impl From<&ToplevelInfo> for Toplevel {
+    fn from(info: &ToplevelInfo) -> Self {
+        Self {
+            handle: ToplevelHandle::Ext(info.foreign_toplevel.clone()),
+            title: info.title.clone(),
+            app_id: info.app_id.clone(),
+            state: info
+                .state
+                .iter()
+                .filter_map(|state| match state {
+                    zcosmic_toplevel_handle_v1::State::Activated => Some(ToplevelState::Activated),
+                    zcosmic_toplevel_handle_v1::State::Maximized => Some(ToplevelState::Maximized),
+                    zcosmic_toplevel_handle_v1::State::Minimized => Some(ToplevelState::Minimized),
+                    zcosmic_toplevel_handle_v1::State::Fullscreen => {
+                        Some(ToplevelState::Fullscreen)
+                    }
+                    _ => None,
+                })
+                .collect(),
+            outputs: info.output.clone(),
+            geometry: info
+                .geometry
+                .iter()
+                .map(|(output, geometry)| {
+                    let geometry = ToplevelGeometry {
+                        x: geometry.x,
+                        y: geometry.y,
+                        width: geometry.width,
+                        height: geometry.height,
+                    };
+                    (output.clone(), geometry)
+                })
+                .collect(),
+            workspaces: info.workspace.clone(),
+            activation_token: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WorkspaceInfo {
+    pub handle: ExtWorkspaceHandleV1,
+    pub group: ExtWorkspaceGroupHandleV1,
+    pub name: String,
+    pub active: bool,
+    pub urgent: bool,
+    /// Outputs of the workspace group the workspace belongs to.
+    pub outputs: Vec<WlOutput>,
 }
 
 #[derive(Clone, Debug)]
 pub enum ToplevelUpdate {
-    Add(ToplevelInfo),
-    Update(ToplevelInfo),
-    Remove(ExtForeignToplevelHandleV1),
+    Add(Toplevel),
+    Update(Toplevel),
+    Remove(ToplevelHandle),
+}
+
+#[derive(Clone, Debug)]
+pub enum WaylandRequest {
+    Toplevel(ToplevelRequest),
+    /// Capture a fresh thumbnail of the toplevel.
+    Screencopy(ToplevelHandle),
+    /// Logical size of the preview area thumbnails are displayed in. Captures
+    /// are scaled to fit this size multiplied by the output scale factor.
+    ThumbnailSize { width: u32, height: u32 },
+    Workspace(WorkspaceRequest),
+}
+
+#[derive(Clone, Debug)]
+pub enum ToplevelRequest {
+    Activate(ToplevelHandle),
+    Close(ToplevelHandle),
+    MoveToWorkspace(ToplevelHandle, ExtWorkspaceHandleV1),
+}
+
+#[derive(Clone, Debug)]
+pub enum WorkspaceRequest {
+    Activate(ExtWorkspaceHandleV1),
+    /// Capture the output the workspace is shown on, if it is active.
+    Capture(ExtWorkspaceHandleV1),
 }
 
 pub fn wayland_subscription() -> iced::Subscription<WaylandUpdate> {
     Subscription::run_with_id(
         std::any::TypeId::of::<WaylandUpdate>(),
         stream::channel(50, move |mut output| async move {
-            let mut state = State::Waiting;
+            let mut state = State::Waiting { failures: 0 };
 
             loop {
                 state = start_listening(state, &mut output).await;
@@ -96,8 +350,19 @@ pub fn wayland_subscription() -> iced::Subscription<WaylandUpdate> {
 }
 
 pub enum State {
-    Waiting,
-    Finished,
+    /// Forwarding updates from the handler thread, starting it if needed.
+    /// `failures` counts handler deaths since the last update was received.
+    Waiting { failures: u32 },
+    /// The handler thread died and will be restarted after a delay.
+    Restarting { failures: u32 },
+}
+
+/// Delay before restarting the handler thread after `failures` deaths in a row.
+fn restart_backoff(failures: u32) -> Duration {
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+    Duration::from_millis(250)
+        .saturating_mul(1 << failures.min(8))
+        .min(MAX_BACKOFF)
 }
 
 async fn start_listening(
@@ -105,45 +370,72 @@ async fn start_listening(
     output: &mut futures::channel::mpsc::Sender<WaylandUpdate>,
 ) -> State {
     match state {
-        State::Waiting => {
+        State::Waiting { failures } => {
             let mut guard = WAYLAND_RX.lock().await;
             let rx = {
                 if guard.is_none() {
+                    let (calloop_tx, calloop_rx) = calloop::channel::channel();
                     let (toplevel_tx, toplevel_rx) = unbounded();
                     let _ = std::thread::spawn(move || {
-                        wayland_handler(toplevel_tx);
+                        if let Err(err) = wayland_handler(toplevel_tx.clone(), calloop_rx) {
+                            tracing::error!("Wayland handler failed: {}", err);
+                            let _ = toplevel_tx.unbounded_send(WaylandUpdate::Error(err));
+                        }
                     });
                     *guard = Some(toplevel_rx);
-                    _ = output.send(WaylandUpdate::Init).await;
+                    _ = output.send(WaylandUpdate::Init(calloop_tx)).await;
                 }
                 guard.as_mut().unwrap()
             };
             match rx.next().await {
                 Some(u) => {
+                    // Anything but an error means the handler is up and running
+                    let failures = if matches!(u, WaylandUpdate::Error(_)) {
+                        failures
+                    } else {
+                        0
+                    };
                     _ = output.send(u).await;
-                    State::Waiting
+                    State::Waiting { failures }
                 }
                 None => {
+                    *guard = None;
                     _ = output.send(WaylandUpdate::Finished).await;
                     tracing::error!("Wayland handler thread died");
-                    State::Finished
+                    State::Restarting {
+                        failures: failures + 1,
+                    }
                 }
             }
         }
-        State::Finished => iced::futures::future::pending().await,
+        State::Restarting { failures } => {
+            let delay = restart_backoff(failures - 1);
+            tracing::info!("Restarting wayland handler in {:?}", delay);
+            tokio::time::sleep(delay).await;
+            State::Waiting { failures }
+        }
     }
 }
 
 struct AppData {
     exit: bool,
     tx: UnboundedSender<WaylandUpdate>,
-    toplevel_info_state: ToplevelInfoState,
+    // Optional states are `None` when the compositor lacks the protocol
+    toplevel_info_state: Option<ToplevelInfoState>,
+    toplevel_manager_state: Option<ToplevelManagerState>,
+    workspace_state: Option<WorkspaceState>,
+    // Only held to keep the wlr manager bound, its events go through dispatch
+    _wlr_toplevel_state: Option<WlrToplevelState>,
     registry_state: RegistryState,
     seat_state: SeatState,
+    output_state: OutputState,
     shm: Shm,
-    screencopy_state: ScreencopyState,
-    conn: Connection,
-    qh: QueueHandle<Self>,
+    screencopy_state: Option<ScreencopyState>,
+    thumbnail_size: (u32, u32),
+    capture_backend: Option<Arc<dyn CaptureBackend>>,
+    captures: Arc<Mutex<HashMap<CaptureKey, Arc<Session>>>>,
+    urgent_workspaces: HashSet<ExtWorkspaceHandleV1>,
+    active_workspaces: HashSet<ExtWorkspaceHandleV1>,
 }
 
 impl ProvidesRegistryState for AppData {
@@ -181,6 +473,18 @@ impl SeatHandler for AppData {
     fn new_seat(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _seat: cosmic::cctk::wayland_client::protocol::wl_seat::WlSeat) {}
 }
 
+impl OutputHandler for AppData {
+    fn output_state(&mut self) -> &mut OutputState {
+        &mut self.output_state
+    }
+
+    fn new_output(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _output: wl_output::WlOutput) {}
+
+    fn update_output(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _output: wl_output::WlOutput) {}
+
+    fn output_destroyed(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _output: wl_output::WlOutput) {}
+}
+
 impl ShmHandler for AppData {
     fn shm_state(&mut self) -> &mut Shm {
         &mut self.shm
@@ -189,7 +493,8 @@ impl ShmHandler for AppData {
 
 impl ToplevelInfoHandler for AppData {
     fn toplevel_info_state(&mut self) -> &mut ToplevelInfoState {
-        &mut self.toplevel_info_state
+        // Only dispatched to when the state was created
+        self.toplevel_info_state.as_mut().unwrap()
     }
 
     fn new_toplevel(
@@ -198,14 +503,15 @@ impl ToplevelInfoHandler for AppData {
         _qh: &QueueHandle<Self>,
         toplevel: &ExtForeignToplevelHandleV1,
     ) {
-        if let Some(info) = self.toplevel_info_state.info(toplevel) {
+        if let Some(info) = self.toplevel_info(toplevel) {
             let _ = self
                 .tx
-                .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Add(info.clone())));
+                .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Add(self.toplevel(info))));
             
             // Trigger screenshot capture for new toplevel
-            self.capture_toplevel_screenshot(toplevel.clone());
+            self.capture_toplevel_screenshot(ToplevelHandle::Ext(toplevel.clone()));
         }
+        self.announce_urgent_toplevels(toplevel);
     }
 
     fn update_toplevel(
@@ -214,11 +520,12 @@ impl ToplevelInfoHandler for AppData {
         _qh: &QueueHandle<Self>,
         toplevel: &ExtForeignToplevelHandleV1,
     ) {
-        if let Some(info) = self.toplevel_info_state.info(toplevel) {
+        if let Some(info) = self.toplevel_info(toplevel) {
             let _ = self
                 .tx
-                .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Update(info.clone())));
+                .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Update(self.toplevel(info))));
         }
+        self.announce_urgent_toplevels(toplevel);
     }
 
     fn toplevel_closed(
@@ -227,31 +534,128 @@ impl ToplevelInfoHandler for AppData {
         _qh: &QueueHandle<Self>,
         toplevel: &ExtForeignToplevelHandleV1,
     ) {
+        let handle = ToplevelHandle::Ext(toplevel.clone());
+        self.cancel_capture(&CaptureKey::Toplevel(handle.clone()));
+        let _ = self
+            .tx
+            .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Remove(handle)));
+        self.announce_urgent_toplevels(toplevel);
+    }
+}
+
+impl WlrToplevelHandler for AppData {
+    fn wlr_toplevel_added(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, toplevel: Toplevel) {
+        let _ = self
+            .tx
+            .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Add(toplevel)));
+    }
+
+    fn wlr_toplevel_updated(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, toplevel: Toplevel) {
+        let _ = self
+            .tx
+            .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Update(toplevel)));
+    }
+
+    fn wlr_toplevel_closed(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        handle: &ZwlrForeignToplevelHandleV1,
+    ) {
+        let handle = ToplevelHandle::Wlr(handle.clone());
+        self.cancel_capture(&CaptureKey::Toplevel(handle.clone()));
         let _ = self
             .tx
-            .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Remove(toplevel.clone())));
+            .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Remove(handle)));
+    }
+}
+
+impl WorkspaceHandler for AppData {
+    fn workspace_state(&mut self) -> &mut WorkspaceState {
+        // Only dispatched to when the state was created
+        self.workspace_state.as_mut().unwrap()
+    }
+
+    fn done(&mut self) {
+        let workspaces = self.workspaces();
+        let urgent: HashSet<_> = workspaces
+            .iter()
+            .filter(|w| w.urgent)
+            .map(|w| w.handle.clone())
+            .collect();
+        let active: HashSet<_> = workspaces
+            .iter()
+            .filter(|w| w.active)
+            .map(|w| w.handle.clone())
+            .collect();
+        let _ = self.tx.unbounded_send(WaylandUpdate::Workspaces(workspaces));
+
+        // Workspaces are only on screen while active, capture each one as it
+        // gets shown so every workspace has a recent frame
+        for workspace in active.difference(&self.active_workspaces) {
+            self.capture_workspace(workspace.clone());
+        }
+        let removed: Vec<_> = self
+            .captures
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|key| match key {
+                CaptureKey::Workspace(workspace) => self
+                    .workspace_state
+                    .as_ref()
+                    .and_then(|state| state.workspace_info(workspace))
+                    .is_none(),
+                CaptureKey::Toplevel(_) => false,
+            })
+            .cloned()
+            .collect();
+        for key in &removed {
+            self.cancel_capture(key);
+        }
+        self.active_workspaces = active;
+
+        // Urgency of toplevels follows their workspace, announce the change
+        if urgent != self.urgent_workspaces {
+            self.urgent_workspaces = urgent;
+            for info in self.toplevel_info_state.iter().flat_map(|state| state.toplevels()) {
+                let _ = self
+                    .tx
+                    .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Update(self.toplevel(info))));
+            }
+        }
+    }
+}
+
+impl ToplevelManagerHandler for AppData {
+    fn toplevel_manager_state(&mut self) -> &mut ToplevelManagerState {
+        // Only dispatched to when the state was created
+        self.toplevel_manager_state.as_mut().unwrap()
+    }
+
+    fn capabilities(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _capabilities: Vec<
+            WEnum<zcosmic_toplevel_manager_v1::ZcosmicToplelevelManagementCapabilitiesV1>,
+        >,
+    ) {
     }
 }
 
 cosmic::cctk::sctk::delegate_seat!(AppData);
+cosmic::cctk::sctk::delegate_output!(AppData);
 cosmic::cctk::sctk::delegate_registry!(AppData);
 cosmic::cctk::sctk::delegate_shm!(AppData);
 cosmic::cctk::delegate_toplevel_info!(AppData);
+cosmic::cctk::delegate_toplevel_manager!(AppData);
+cosmic::cctk::delegate_workspace!(AppData);
 cosmic::cctk::delegate_screencopy!(AppData, session: [SessionData], frame: [FrameData]);
+cosmic::cctk::wayland_client::delegate_dispatch!(AppData: [ZwlrForeignToplevelManagerV1: ()] => WlrToplevelState);
+cosmic::cctk::wayland_client::delegate_dispatch!(AppData: [ZwlrForeignToplevelHandleV1: WlrToplevelData] => WlrToplevelState);
 
-// Screenshot capture data structures
-#[derive(Default)]
-struct SessionInner {
-    formats: Option<Formats>,
-    res: Option<Result<(), WEnum<FailureReason>>>,
-}
-
-#[derive(Default)]
-struct Session {
-    condvar: Condvar,
-    inner: Mutex<SessionInner>,
-}
-
+// Screencopy user data, pointing at the capture they belong to
 #[derive(Default)]
 struct SessionData {
     session: Arc<Session>,
@@ -260,24 +664,11 @@ struct SessionData {
 
 struct FrameData {
     frame_data: ScreencopyFrameData,
-    session: CaptureSession,
+    session: Arc<Session>,
 }
 
-impl Session {
-    pub fn for_session(session: &CaptureSession) -> Option<&Self> {
-        Some(&session.data::<SessionData>()?.session)
-    }
-
-    fn update<F: FnOnce(&mut SessionInner)>(&self, f: F) {
-        f(&mut self.inner.lock().unwrap());
-        self.condvar.notify_all();
-    }
-
-    fn wait_while<F: FnMut(&SessionInner) -> bool>(&self, mut f: F) -> MutexGuard<'_, SessionInner> {
-        self.condvar
-            .wait_while(self.inner.lock().unwrap(), |data| f(data))
-            .unwrap()
-    }
+fn session_for(session: &CaptureSession) -> Option<&Arc<Session>> {
+    Some(&session.data::<SessionData>()?.session)
 }
 
 impl ScreencopySessionDataExt for SessionData {
@@ -294,7 +685,8 @@ impl ScreencopyFrameDataExt for FrameData {
 
 impl ScreencopyHandler for AppData {
     fn screencopy_state(&mut self) -> &mut ScreencopyState {
-        &mut self.screencopy_state
+        // Only dispatched to when the state was created
+        self.screencopy_state.as_mut().unwrap()
     }
 
     fn init_done(
@@ -304,7 +696,7 @@ impl ScreencopyHandler for AppData {
         session: &CaptureSession,
         formats: &Formats,
     ) {
-        Session::for_session(session).unwrap().update(|data| {
+        session_for(session).unwrap().update(|data| {
             data.formats = Some(formats.clone());
         });
     }
@@ -317,7 +709,7 @@ impl ScreencopyHandler for AppData {
         _frame: Frame,
     ) {
         let session = &screencopy_frame.data::<FrameData>().unwrap().session;
-        Session::for_session(session).unwrap().update(|data| {
+        session.update(|data| {
             data.res = Some(Ok(()));
         });
     }
@@ -330,12 +722,16 @@ impl ScreencopyHandler for AppData {
         reason: WEnum<FailureReason>,
     ) {
         let session = &screencopy_frame.data::<FrameData>().unwrap().session;
-        Session::for_session(session).unwrap().update(|data| {
+        session.update(|data| {
             data.res = Some(Err(reason));
         });
     }
 
-    fn stopped(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _session: &CaptureSession) {}
+    fn stopped(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, session: &CaptureSession) {
+        if let Some(session) = session_for(session) {
+            session.update(|data| data.stopped = true);
+        }
+    }
 }
 
 impl Dispatch<wl_shm_pool::WlShmPool, ()> for AppData {
@@ -362,25 +758,95 @@ impl Dispatch<wl_buffer::WlBuffer, ()> for AppData {
     }
 }
 
-struct CaptureData {
+/// Default capture backend, using ext-image-copy-capture on the handler's
+/// connection.
+struct ExtCapture {
+    capabilities: CaptureCapabilities,
     qh: QueueHandle<AppData>,
     conn: Connection,
     wl_shm: wl_shm::WlShm,
     capturer: Capturer,
 }
 
-impl CaptureData {
-    pub fn capture_source_shm_fd<Fd: AsFd>(
+/// Destroys the shm pool and buffer of a capture however it ends.
+struct ShmBuffer {
+    pool: wl_shm_pool::WlShmPool,
+    buffer: wl_buffer::WlBuffer,
+}
+
+impl Drop for ShmBuffer {
+    fn drop(&mut self) {
+        self.buffer.destroy();
+        self.pool.destroy();
+    }
+}
+
+impl CaptureBackend for ExtCapture {
+    fn name(&self) -> &'static str {
+        "ext-image-copy-capture"
+    }
+
+    fn capabilities(&self) -> CaptureCapabilities {
+        self.capabilities
+    }
+
+    fn supports(&self, source: &CaptureSource) -> bool {
+        match source {
+            // wlr handles cannot be turned into capture sources
+            CaptureSource::Toplevel {
+                handle: ToplevelHandle::Wlr(_),
+                ..
+            } => false,
+            CaptureSource::Toplevel { .. } => self.capabilities.toplevels,
+            CaptureSource::Output { .. } => self.capabilities.outputs,
+        }
+    }
+
+    fn capture(
         &self,
-        source: &ExtForeignToplevelHandleV1,
+        source: &CaptureSource,
+        session: Arc<Session>,
+    ) -> Result<image::RgbaImage, CaptureError> {
+        use std::ffi::CStr;
+        let name = unsafe { CStr::from_bytes_with_nul_unchecked(b"cosmic-launcher-screenshot\0") };
+        let fd = rustix::fs::memfd_create(name, rustix::fs::MemfdFlags::CLOEXEC)
+            .map_err(|_| CaptureError::Buffer)?;
+
+        let source = match source {
+            CaptureSource::Toplevel {
+                handle: ToplevelHandle::Ext(handle),
+                ..
+            } => cctk_screencopy::CaptureSource::Toplevel(handle.clone()),
+            CaptureSource::Output { output, .. } => {
+                cctk_screencopy::CaptureSource::Output(output.clone())
+            }
+            CaptureSource::Toplevel {
+                handle: ToplevelHandle::Wlr(_),
+                ..
+            } => {
+                return Err(CaptureError::UnsupportedSource);
+            }
+        };
+
+        self.capture_source_shm_fd(&source, session, fd, None)?
+            .image()
+            .map_err(|err| CaptureError::Image(err.to_string()))
+    }
+}
+
+impl ExtCapture {
+    fn capture_source_shm_fd<Fd: AsFd>(
+        &self,
+        source: &cctk_screencopy::CaptureSource,
+        session: Arc<Session>,
         fd: Fd,
         len: Option<u32>,
-    ) -> Option<ShmImage<Fd>> {
-        let session = Arc::new(Session::default());
+    ) -> Result<ShmImage<Fd>, CaptureError> {
+        let deadline = Instant::now() + CAPTURE_TIMEOUT;
         let capture_session = self
             .capturer
             .create_session(
-                &CaptureSource::Toplevel(source.clone()),
+                source,
                 CaptureOptions::empty(),
                 &self.qh,
                 SessionData {
@@ -388,38 +854,40 @@ impl CaptureData {
                     session_data: Default::default(),
                 },
             )
-            .ok()?;
-        
-        self.conn.flush().ok()?;
+            .map_err(|_| CaptureError::Session)?;
+
+        self.conn.flush().map_err(|_| CaptureError::Connection)?;
 
         let formats = session
-            .wait_while(|data| data.formats.is_none())
+            .wait_while(deadline, |data| data.formats.is_none())?
             .formats
-            .take()?;
+            .take()
+            .ok_or(CaptureError::Session)?;
         let (width, height) = formats.buffer_size;
 
         if width == 0 || height == 0 {
-            return None;
+            return Err(CaptureError::Buffer);
         }
 
-        if !formats
-            .shm_formats
-            .contains(&wl_shm::Format::Abgr8888.into())
-        {
-            tracing::error!("No suitable buffer format found");
+        let Some(format) = SHM_FORMATS
+            .iter()
+            .copied()
+            .find(|format| formats.shm_formats.contains(&(*format).into()))
+        else {
             tracing::warn!("Available formats: {:#?}", formats);
-            return None;
+            return Err(CaptureError::UnsupportedFormat);
         };
 
-        let buf_len = width * height * 4;
+        let stride = width * 4;
+        let buf_len = stride * height;
         if let Some(len) = len {
             if len != buf_len {
-                return None;
+                return Err(CaptureError::Buffer);
             }
         } else if rustix::fs::ftruncate(&fd, buf_len as _).is_err() {
-            return None;
+            return Err(CaptureError::Buffer);
         }
-        
+
         let pool = self
             .wl_shm
             .create_pool(fd.as_fd(), buf_len as i32, &self.qh, ());
@@ -427,143 +895,487 @@ impl CaptureData {
             0,
             width as i32,
             height as i32,
-            width as i32 * 4,
-            wl_shm::Format::Abgr8888,
+            stride as i32,
+            format,
             &self.qh,
             (),
         );
+        let shm_buffer = ShmBuffer { pool, buffer };
 
         capture_session.capture(
-            &buffer,
+            &shm_buffer.buffer,
             &[],
             &self.qh,
             FrameData {
                 frame_data: Default::default(),
-                session: capture_session.clone(),
+                session: session.clone(),
             },
         );
-        self.conn.flush().ok()?;
+        self.conn.flush().map_err(|_| CaptureError::Connection)?;
 
         let res = session
-            .wait_while(|data| data.res.is_none())
+            .wait_while(deadline, |data| data.res.is_none())?
             .res
-            .take()?;
-        pool.destroy();
-        buffer.destroy();
-
-        if res.is_ok() {
-            Some(ShmImage { fd, width, height })
-        } else {
-            None
-        }
+            .take()
+            .ok_or(CaptureError::Session)?;
+
+        // Frames only hold our `Session`, so dropping the last handle here
+        // destroys the capture session on the compositor side.
+        drop(capture_session);
+        drop(shm_buffer);
+        let _ = self.conn.flush();
+
+        res.map_err(CaptureError::Failed)?;
+        Ok(ShmImage {
+            fd,
+            width,
+            height,
+            stride,
+            format,
+        })
     }
 }
 
-pub struct ShmImage<T: AsFd> {
-    fd: T,
-    pub width: u32,
-    pub height: u32,
-}
+impl AppData {
+    fn toplevel_info(&self, handle: &ExtForeignToplevelHandleV1) -> Option<&ToplevelInfo> {
+        self.toplevel_info_state.as_ref()?.info(handle)
+    }
 
-impl<T: AsFd> ShmImage<T> {
-    pub fn image(&self) -> Result<image::RgbaImage, Box<dyn std::error::Error + Send + Sync>> {
-        let mmap = unsafe { memmap2::Mmap::map(&self.fd.as_fd())? };
-        
-        // Convert ABGR to RGBA
-        let mut rgba_data = vec![0u8; (self.width * self.height * 4) as usize];
-        for i in 0..(self.width * self.height) as usize {
-            let base = i * 4;
-            // ABGR -> RGBA
-            rgba_data[base] = mmap[base + 2];     // R = B
-            rgba_data[base + 1] = mmap[base + 1]; // G = G
-            rgba_data[base + 2] = mmap[base];     // B = R
-            rgba_data[base + 3] = mmap[base + 3]; // A = A
+    /// Converts `info`, marking it urgent when it is the only unfocused
+    /// toplevel of an urgent workspace.
+    fn toplevel(&self, info: &ToplevelInfo) -> Toplevel {
+        let mut toplevel = Toplevel::from(info);
+        let unfocused =
+            |info: &ToplevelInfo| !info.state.contains(&zcosmic_toplevel_handle_v1::State::Activated);
+        let sole_candidate = unfocused(info)
+            && info
+                .workspace
+                .iter()
+                .filter(|workspace| self.urgent_workspaces.contains(*workspace))
+                .any(|workspace| {
+                    !self
+                        .toplevel_info_state
+                        .iter()
+                        .flat_map(|state| state.toplevels())
+                        .any(|other| {
+                            other.foreign_toplevel != info.foreign_toplevel
+                                && other.workspace.contains(workspace)
+                                && unfocused(other)
+                        })
+                });
+        if sole_candidate {
+            toplevel.state.insert(ToplevelState::Urgent);
         }
-        
-        image::RgbaImage::from_raw(self.width, self.height, rgba_data)
-            .ok_or_else(|| "ShmImage had incorrect size".into())
+        toplevel
     }
-}
 
-impl AppData {
-    fn capture_toplevel_screenshot(&self, handle: ExtForeignToplevelHandleV1) {
-        let tx = self.tx.clone();
-        let capture_data = CaptureData {
-            qh: self.qh.clone(),
-            conn: self.conn.clone(),
-            wl_shm: self.shm.wl_shm().clone(),
-            capturer: self.screencopy_state.capturer().clone(),
-        };
-        
-        std::thread::spawn(move || {
-            use std::ffi::CStr;
-            let name = unsafe { CStr::from_bytes_with_nul_unchecked(b"cosmic-launcher-screenshot\0") };
-            let Ok(fd) = rustix::fs::memfd_create(name, rustix::fs::MemfdFlags::CLOEXEC) else {
-                tracing::error!("Failed to get fd for capture");
-                return;
-            };
+    /// Resends the toplevels of urgent workspaces, whose urgency depends on
+    /// the other toplevels there.
+    fn announce_urgent_toplevels(&self, except: &ExtForeignToplevelHandleV1) {
+        if self.urgent_workspaces.is_empty() {
+            return;
+        }
+        for info in self
+            .toplevel_info_state
+            .iter()
+            .flat_map(|state| state.toplevels())
+            .filter(|info| &info.foreign_toplevel != except)
+            .filter(|info| !info.workspace.is_disjoint(&self.urgent_workspaces))
+        {
+            let _ = self
+                .tx
+                .unbounded_send(WaylandUpdate::Toplevel(ToplevelUpdate::Update(self.toplevel(info))));
+        }
+    }
 
-            let img = capture_data.capture_source_shm_fd(&handle, fd, None);
-            if let Some(img) = img {
-                let Ok(mut img) = img.image() else {
-                    tracing::error!("Failed to get RgbaImage");
+    fn handle_request(&mut self, request: WaylandRequest) {
+        match request {
+            WaylandRequest::Toplevel(ToplevelRequest::Activate(handle)) => {
+                let Some(seat) = self.seat_state.seats().next() else {
+                    tracing::warn!("No seat available to activate toplevel");
+                    return;
+                };
+                match handle {
+                    ToplevelHandle::Ext(handle) => {
+                        let Some((manager, cosmic_toplevel)) = self.cosmic_toplevel(&handle) else {
+                            return;
+                        };
+                        manager.manager.activate(cosmic_toplevel, &seat);
+                    }
+                    ToplevelHandle::Wlr(handle) => handle.activate(&seat),
+                }
+            }
+            WaylandRequest::Toplevel(ToplevelRequest::MoveToWorkspace(handle, workspace)) => {
+                let ToplevelHandle::Ext(handle) = handle else {
+                    tracing::warn!("Moving windows between workspaces is not supported on wlr");
                     return;
                 };
+                let Some(output) = self.workspace_output(&workspace) else {
+                    tracing::warn!("No output for workspace {:?}", workspace);
+                    return;
+                };
+                let Some((manager, cosmic_toplevel)) = self.cosmic_toplevel(&handle) else {
+                    return;
+                };
+                manager
+                    .manager
+                    .move_to_ext_workspace(cosmic_toplevel, &workspace, &output);
+            }
+            WaylandRequest::Workspace(WorkspaceRequest::Activate(workspace)) => {
+                let Some(state) = &self.workspace_state else {
+                    tracing::warn!("Workspaces unavailable, cannot activate {:?}", workspace);
+                    return;
+                };
+                workspace.activate();
+                if let Ok(manager) = state.workspace_manager().get() {
+                    manager.commit();
+                }
+            }
+            WaylandRequest::Workspace(WorkspaceRequest::Capture(workspace)) => {
+                self.capture_workspace(workspace);
+            }
+            WaylandRequest::Toplevel(ToplevelRequest::Close(handle)) => match handle {
+                ToplevelHandle::Ext(handle) => {
+                    let Some((manager, cosmic_toplevel)) = self.cosmic_toplevel(&handle) else {
+                        return;
+                    };
+                    manager.manager.close(cosmic_toplevel);
+                }
+                ToplevelHandle::Wlr(handle) => handle.close(),
+            },
+            WaylandRequest::Screencopy(handle) => {
+                let known = match &handle {
+                    ToplevelHandle::Ext(handle) => self.toplevel_info(handle).is_some(),
+                    ToplevelHandle::Wlr(_) => true,
+                };
+                if known {
+                    self.capture_toplevel_screenshot(handle);
+                }
+            }
+            WaylandRequest::ThumbnailSize { width, height } => {
+                if width == 0 || height == 0 || self.thumbnail_size == (width, height) {
+                    return;
+                }
+                self.thumbnail_size = (width, height);
 
-                // Resize to 128x128 for thumbnail
-                let max = img.width().max(img.height());
-                let ratio = max as f32 / 128.0;
+                // Previews were sized for the old layout, capture them again
+                let handles: Vec<_> = self
+                    .toplevel_info_state
+                    .iter()
+                    .flat_map(|state| state.toplevels())
+                    .map(|info| ToplevelHandle::Ext(info.foreign_toplevel.clone()))
+                    .collect();
+                for handle in handles {
+                    self.capture_toplevel_screenshot(handle);
+                }
+            }
+        }
+    }
 
-                if ratio > 1.0 {
-                    let new_width = (img.width() as f32 / ratio).round();
-                    let new_height = (img.height() as f32 / ratio).round();
+    /// Toplevel manager and cosmic handle needed to act on an ext toplevel.
+    fn cosmic_toplevel(
+        &self,
+        handle: &ExtForeignToplevelHandleV1,
+    ) -> Option<(&ToplevelManagerState, &zcosmic_toplevel_handle_v1::ZcosmicToplevelHandleV1)> {
+        let Some(manager) = &self.toplevel_manager_state else {
+            tracing::warn!("Toplevel management unavailable for {:?}", handle);
+            return None;
+        };
+        let Some(cosmic_toplevel) = self
+            .toplevel_info(handle)
+            .and_then(|info| info.cosmic_toplevel.as_ref())
+        else {
+            tracing::warn!("Unknown toplevel {:?}", handle);
+            return None;
+        };
+        Some((manager, cosmic_toplevel))
+    }
+
+    /// Largest scale factor of the outputs the toplevel is shown on.
+    fn toplevel_scale(&self, handle: &ToplevelHandle) -> u32 {
+        let ToplevelHandle::Ext(handle) = handle else {
+            return 1;
+        };
+        self.toplevel_info(handle)
+            .into_iter()
+            .flat_map(|info| info.output.iter())
+            .filter_map(|output| self.output_state.info(output))
+            .map(|info| info.scale_factor.max(1) as u32)
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn workspaces(&self) -> Vec<WorkspaceInfo> {
+        let Some(state) = &self.workspace_state else {
+            return Vec::new();
+        };
+        let mut workspaces = Vec::new();
+        for group in state.workspace_groups() {
+            let mut group_workspaces: Vec<_> = group
+                .workspaces
+                .iter()
+                .filter_map(|handle| state.workspace_info(handle))
+                .collect();
+            group_workspaces.sort_by(|a, b| a.coordinates.cmp(&b.coordinates));
+            workspaces.extend(group_workspaces.into_iter().map(|workspace| WorkspaceInfo {
+                handle: workspace.handle.clone(),
+                group: group.handle.clone(),
+                name: workspace.name.clone(),
+                active: workspace.state.contains(ext_workspace_handle_v1::State::Active),
+                urgent: workspace.state.contains(ext_workspace_handle_v1::State::Urgent),
+                outputs: group.outputs.clone(),
+            }));
+        }
+        workspaces
+    }
+
+    /// First output of the group the workspace belongs to.
+    fn workspace_output(&self, workspace: &ExtWorkspaceHandleV1) -> Option<WlOutput> {
+        self.workspace_state
+            .as_ref()?
+            .workspace_groups()
+            .find(|group| group.workspaces.contains(workspace))?
+            .outputs
+            .first()
+            .cloned()
+    }
+
+    /// Captures the output showing `workspace`. Only active workspaces are
+    /// on screen, so this is called whenever one becomes active and the app
+    /// keeps the last frame of each workspace.
+    fn capture_workspace(&self, workspace: ExtWorkspaceHandleV1) {
+        let Some(backend) = &self.capture_backend else {
+            return;
+        };
+        let active = self
+            .workspace_state
+            .as_ref()
+            .and_then(|state| state.workspace_info(&workspace))
+            .is_some_and(|info| info.state.contains(ext_workspace_handle_v1::State::Active));
+        let Some(output) = self.workspace_output(&workspace).filter(|_| active) else {
+            return;
+        };
+        let source = CaptureSource::Output {
+            output: output.clone(),
+            name: self.output_state.info(&output).and_then(|info| info.name),
+        };
+        if !backend.supports(&source) {
+            return;
+        }
+        let scale = self
+            .output_state
+            .info(&output)
+            .map_or(1, |info| info.scale_factor.max(1) as u32);
 
-                    img = image::imageops::resize(
-                        &img,
-                        new_width as u32,
-                        new_height as u32,
-                        image::imageops::FilterType::Lanczos3,
-                    );
+        self.spawn_capture(
+            CaptureKey::Workspace(workspace.clone()),
+            source,
+            scale,
+            move |res| match res {
+                Ok(img) => Some(WaylandUpdate::WorkspaceImage(workspace, img)),
+                Err(err) => {
+                    tracing::warn!("Failed to capture workspace {:?}: {}", workspace, err);
+                    None
                 }
+            },
+        );
+    }
+
+    /// Capture source for `handle`, with the title and app id backends
+    /// without access to the handle match windows by.
+    fn toplevel_source(&self, handle: &ToplevelHandle) -> Option<CaptureSource> {
+        let (title, app_id) = match handle {
+            ToplevelHandle::Ext(ext) => {
+                let info = self.toplevel_info(ext)?;
+                (info.title.clone(), info.app_id.clone())
+            }
+            ToplevelHandle::Wlr(wlr) => wlr.data::<WlrToplevelData>()?.title_and_app_id(),
+        };
+        Some(CaptureSource::Toplevel {
+            handle: handle.clone(),
+            title,
+            app_id,
+        })
+    }
 
-                if let Err(err) =
-                    tx.unbounded_send(WaylandUpdate::Image(handle, WaylandImage::new(img)))
-                {
+    /// Wakes up a capture thread still waiting on a closed toplevel or a
+    /// removed workspace.
+    fn cancel_capture(&self, key: &CaptureKey) {
+        if let Some(session) = self.captures.lock().unwrap().remove(key) {
+            session.update(|data| data.cancelled = true);
+        }
+    }
+
+    fn capture_toplevel_screenshot(&self, handle: ToplevelHandle) {
+        let Some(backend) = &self.capture_backend else {
+            // Without a capture backend the switcher shows app icons instead
+            return;
+        };
+        let Some(source) = self.toplevel_source(&handle) else {
+            return;
+        };
+        if !backend.supports(&source) {
+            return;
+        }
+        let scale = self.toplevel_scale(&handle);
+
+        self.spawn_capture(
+            CaptureKey::Toplevel(handle.clone()),
+            source,
+            scale,
+            move |res| {
+                Some(match res {
+                    Ok(img) => WaylandUpdate::Image(handle, img),
+                    Err(err) => {
+                        tracing::warn!("Failed to capture {:?}: {}", handle, err);
+                        WaylandUpdate::CaptureFailed(handle, err)
+                    }
+                })
+            },
+        );
+    }
+
+    /// Captures `source` on its own thread, registered in `captures` under
+    /// `key` so it can be cancelled. Does nothing while a capture for `key`
+    /// is in flight.
+    fn spawn_capture<F>(&self, key: CaptureKey, source: CaptureSource, scale: u32, update: F)
+    where
+        F: FnOnce(Result<WaylandImage, CaptureError>) -> Option<WaylandUpdate> + Send + 'static,
+    {
+        let Some(backend) = self.capture_backend.clone() else {
+            return;
+        };
+        let session = {
+            let mut captures = self.captures.lock().unwrap();
+            if captures.contains_key(&key) {
+                return;
+            }
+            let session = Arc::new(Session::default());
+            captures.insert(key.clone(), session.clone());
+            session
+        };
+
+        let tx = self.tx.clone();
+        let captures = self.captures.clone();
+        let max_size = (
+            self.thumbnail_size.0 * scale,
+            self.thumbnail_size.1 * scale,
+        );
+
+        std::thread::spawn(move || {
+            let res = capture::thumbnail(backend.as_ref(), &source, session.clone(), max_size);
+
+            // Forget the capture unless it was cancelled and removed already
+            {
+                let mut captures = captures.lock().unwrap();
+                if captures.get(&key).is_some_and(|s| Arc::ptr_eq(s, &session)) {
+                    captures.remove(&key);
+                }
+            }
+
+            if let Some(update) = update(res) {
+                if let Err(err) = tx.unbounded_send(update) {
                     tracing::error!("Failed to send image event to subscription {err:?}");
-                };
-            } else {
-                tracing::error!("Failed to capture image");
+                }
             }
         });
     }
 }
 
-fn wayland_handler(tx: UnboundedSender<WaylandUpdate>) {
-    let conn = Connection::connect_to_env().unwrap();
-    let (globals, mut event_queue) = registry_queue_init(&conn).unwrap();
+fn wayland_handler(
+    tx: UnboundedSender<WaylandUpdate>,
+    rx: calloop::channel::Channel<WaylandRequest>,
+) -> Result<(), WaylandError> {
+    let conn = Connection::connect_to_env().map_err(|err| WaylandError::Connect(err.to_string()))?;
+    let (globals, event_queue) =
+        registry_queue_init(&conn).map_err(|err| WaylandError::Registry(err.to_string()))?;
+
+    let mut event_loop = calloop::EventLoop::<AppData>::try_new()
+        .map_err(|err| WaylandError::EventLoop(err.to_string()))?;
     let qh = event_queue.handle();
-    
+    let wayland_source = WaylandSource::new(conn.clone(), event_queue);
+    let handle = event_loop.handle();
+    wayland_source
+        .insert(handle.clone())
+        .map_err(|err| WaylandError::EventLoop(err.to_string()))?;
+
+    handle
+        .insert_source(rx, |event, (), state| match event {
+            calloop::channel::Event::Msg(req) => state.handle_request(req),
+            calloop::channel::Event::Closed => {
+                state.exit = true;
+            }
+        })
+        .map_err(|err| WaylandError::EventLoop(err.to_string()))?;
+
+    let capabilities = Capabilities::from_globals(&globals);
+    tracing::info!("{}", capabilities.report());
+    let _ = tx.unbounded_send(WaylandUpdate::Capabilities(capabilities.clone()));
+
     let registry_state = RegistryState::new(&globals);
-    
+    let shm = Shm::bind(&globals, &qh).map_err(|_| WaylandError::MissingGlobal("wl_shm"))?;
+
+    let screencopy_state = capabilities
+        .thumbnails()
+        .then(|| ScreencopyState::new(&globals, &qh));
+    let capture_backend = match CaptureBackendKind::from_env() {
+        #[cfg(feature = "xcap")]
+        CaptureBackendKind::Xcap => Some(Arc::new(capture::XcapCapture) as Arc<dyn CaptureBackend>),
+        #[cfg(feature = "wayshot")]
+        CaptureBackendKind::Wayshot => Some(Arc::new(capture::WayshotCapture) as Arc<dyn CaptureBackend>),
+        #[cfg(feature = "portal")]
+        CaptureBackendKind::Portal => Some(Arc::new(capture::PortalCapture) as Arc<dyn CaptureBackend>),
+        CaptureBackendKind::Ext => screencopy_state.as_ref().map(|state| {
+            Arc::new(ExtCapture {
+                capabilities: CaptureCapabilities {
+                    toplevels: true,
+                    outputs: capabilities.has("ext_output_image_capture_source_manager_v1"),
+                },
+                qh: qh.clone(),
+                conn: conn.clone(),
+                wl_shm: shm.wl_shm().clone(),
+                capturer: state.capturer().clone(),
+            }) as Arc<dyn CaptureBackend>
+        }),
+        CaptureBackendKind::None => None,
+    };
+    match &capture_backend {
+        Some(backend) => tracing::info!("Capturing thumbnails with {}", backend.name()),
+        None => tracing::info!("No capture backend, thumbnails disabled"),
+    }
+
+    let backend = capabilities.toplevel_backend();
+    let cosmic = backend == Some(ToplevelBackend::Cosmic);
     let mut app_data = AppData {
         exit: false,
         tx,
-        toplevel_info_state: ToplevelInfoState::new(&registry_state, &qh),
+        toplevel_info_state: cosmic.then(|| ToplevelInfoState::new(&registry_state, &qh)),
+        toplevel_manager_state: (cosmic && capabilities.toplevel_management())
+            .then(|| ToplevelManagerState::new(&registry_state, &qh)),
+        workspace_state: capabilities
+            .workspaces()
+            .then(|| WorkspaceState::new(&registry_state, &qh)),
+        _wlr_toplevel_state: (backend == Some(ToplevelBackend::Wlr))
+            .then(|| WlrToplevelState::new(&globals, &qh))
+            .flatten(),
         registry_state,
         seat_state: SeatState::new(&globals, &qh),
-        shm: Shm::bind(&globals, &qh).unwrap(),
-        screencopy_state: ScreencopyState::new(&globals, &qh),
-        conn,
-        qh,
+        output_state: OutputState::new(&globals, &qh),
+        shm,
+        screencopy_state,
+        thumbnail_size: (220, 125),
+        capture_backend,
+        captures: Arc::new(Mutex::new(HashMap::new())),
+        urgent_workspaces: HashSet::new(),
+        active_workspaces: HashSet::new(),
     };
 
     loop {
         if app_data.exit {
-            break;
-        }
-        if let Err(e) = event_queue.blocking_dispatch(&mut app_data) {
-            tracing::error!("Wayland event dispatch failed: {}", e);
-            break;
+            return Ok(());
         }
+        event_loop
+            .dispatch(None, &mut app_data)
+            .map_err(|err| WaylandError::Dispatch(err.to_string()))?;
     }
 }
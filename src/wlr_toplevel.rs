@@ -0,0 +1,175 @@
+// Copyright 2023 System76 <info@system76.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Toplevel tracking through wlr-foreign-toplevel-management.
+//!
+//! Used on wlroots based compositors (sway, river, Hyprland, ...) which do not
+//! implement the cosmic toplevel protocols. Toplevels are reported with the
+//! same [`Toplevel`] type as the cosmic backend.
+
+use crate::wayland_subscription::{Toplevel, ToplevelHandle, ToplevelState};
+use cosmic::cctk::wayland_client::{
+    event_created_child, globals::GlobalList, protocol::wl_output::WlOutput, Connection,
+    Dispatch, QueueHandle,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+pub struct WlrToplevelState {
+    _manager: ZwlrForeignToplevelManagerV1,
+}
+
+impl WlrToplevelState {
+    pub fn new<D>(globals: &GlobalList, qh: &QueueHandle<D>) -> Option<Self>
+    where
+        D: Dispatch<ZwlrForeignToplevelManagerV1, ()> + 'static,
+    {
+        let manager = globals.bind(qh, 1..=3, ()).ok()?;
+        Some(Self { _manager: manager })
+    }
+}
+
+pub trait WlrToplevelHandler: Sized {
+    /// A toplevel sent its first `done` event.
+    fn wlr_toplevel_added(&mut self, conn: &Connection, qh: &QueueHandle<Self>, toplevel: Toplevel);
+
+    /// A known toplevel changed its title, app id, state or outputs.
+    fn wlr_toplevel_updated(&mut self, conn: &Connection, qh: &QueueHandle<Self>, toplevel: Toplevel);
+
+    fn wlr_toplevel_closed(
+        &mut self,
+        conn: &Connection,
+        qh: &QueueHandle<Self>,
+        handle: &ZwlrForeignToplevelHandleV1,
+    );
+}
+
+/// Properties of a toplevel, double buffered until the next `done` event.
+#[derive(Default)]
+pub struct WlrToplevelData {
+    inner: Mutex<WlrToplevelInner>,
+}
+
+impl WlrToplevelData {
+    /// Title and app id as of the last `done` event or later.
+    pub fn title_and_app_id(&self) -> (String, String) {
+        let inner = self.inner.lock().unwrap();
+        (inner.title.clone(), inner.app_id.clone())
+    }
+}
+
+#[derive(Default)]
+struct WlrToplevelInner {
+    title: String,
+    app_id: String,
+    state: HashSet<ToplevelState>,
+    outputs: HashSet<WlOutput>,
+    announced: bool,
+}
+
+impl<D> Dispatch<ZwlrForeignToplevelManagerV1, (), D> for WlrToplevelState
+where
+    D: Dispatch<ZwlrForeignToplevelManagerV1, ()>
+        + Dispatch<ZwlrForeignToplevelHandleV1, WlrToplevelData>
+        + WlrToplevelHandler
+        + 'static,
+{
+    fn event(
+        _state: &mut D,
+        _manager: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<D>,
+    ) {
+        // New toplevels are announced once their handle sends `done`
+        if let zwlr_foreign_toplevel_manager_v1::Event::Finished = event {
+            tracing::warn!("wlr foreign toplevel manager finished");
+        }
+    }
+
+    event_created_child!(D, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, WlrToplevelData::default())
+    ]);
+}
+
+impl<D> Dispatch<ZwlrForeignToplevelHandleV1, WlrToplevelData, D> for WlrToplevelState
+where
+    D: Dispatch<ZwlrForeignToplevelHandleV1, WlrToplevelData> + WlrToplevelHandler + 'static,
+{
+    fn event(
+        state: &mut D,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        data: &WlrToplevelData,
+        conn: &Connection,
+        qh: &QueueHandle<D>,
+    ) {
+        let mut inner = data.inner.lock().unwrap();
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => inner.title = title,
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => inner.app_id = app_id,
+            zwlr_foreign_toplevel_handle_v1::Event::OutputEnter { output } => {
+                inner.outputs.insert(output);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::OutputLeave { output } => {
+                inner.outputs.remove(&output);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::State { state: states } => {
+                inner.state = states
+                    .chunks_exact(4)
+                    .filter_map(|raw| {
+                        let raw = u32::from_ne_bytes([raw[0], raw[1], raw[2], raw[3]]);
+                        match zwlr_foreign_toplevel_handle_v1::State::try_from(raw).ok()? {
+                            zwlr_foreign_toplevel_handle_v1::State::Maximized => {
+                                Some(ToplevelState::Maximized)
+                            }
+                            zwlr_foreign_toplevel_handle_v1::State::Minimized => {
+                                Some(ToplevelState::Minimized)
+                            }
+                            zwlr_foreign_toplevel_handle_v1::State::Activated => {
+                                Some(ToplevelState::Activated)
+                            }
+                            zwlr_foreign_toplevel_handle_v1::State::Fullscreen => {
+                                Some(ToplevelState::Fullscreen)
+                            }
+                            _ => None,
+                        }
+                    })
+                    .collect();
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Done => {
+                let toplevel = Toplevel {
+                    handle: ToplevelHandle::Wlr(handle.clone()),
+                    title: inner.title.clone(),
+                    app_id: inner.app_id.clone(),
+                    state: inner.state.clone(),
+                    outputs: inner.outputs.clone(),
+                    // wlr-foreign-toplevel does not report positions or workspaces
+                    geometry: HashMap::new(),
+                    workspaces: HashSet::new(),
+                    activation_token: None,
+                };
+                let announced = std::mem::replace(&mut inner.announced, true);
+                drop(inner);
+                if announced {
+                    state.wlr_toplevel_updated(conn, qh, toplevel);
+                } else {
+                    state.wlr_toplevel_added(conn, qh, toplevel);
+                }
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => {
+                drop(inner);
+                state.wlr_toplevel_closed(conn, qh, handle);
+                handle.destroy();
+            }
+            _ => {}
+        }
+    }
+}